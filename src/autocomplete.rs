@@ -0,0 +1,180 @@
+use crate::board::BoardState;
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::events::{AutoMoveEvent, DoubleClickEvent, MoveEvent, NewGameEvent};
+use crate::state::AppState;
+use crate::utils::button::spawn_button;
+use crate::utils::dragging::{Dragging, find_source};
+use bevy::prelude::*;
+
+const AUTO_COMPLETE_INTERVAL: f32 = 0.15;
+
+pub struct AutoCompletePlugin;
+
+impl Plugin for AutoCompletePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_auto_complete_button)
+            .add_systems(OnExit(AppState::Playing), stop_auto_complete)
+            .add_systems(
+                Update,
+                (
+                    stop_auto_complete.run_if(on_event::<NewGameEvent>),
+                    update_auto_complete_button,
+                    (start_auto_complete, send_card_home).run_if(in_state(AppState::Playing)),
+                    run_auto_complete
+                        .run_if(in_state(AppState::Playing))
+                        .run_if(resource_exists::<AutoComplete>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Present while cards are being sent home automatically.
+#[derive(Resource)]
+pub struct AutoComplete {
+    timer: Timer,
+    /// Only send cards that `GameState::is_safe_home` allows, rather than
+    /// finishing a fully revealed game.
+    safe_only: bool,
+}
+
+#[derive(Component)]
+struct AutoCompleteButton;
+
+#[derive(Component)]
+struct AutoCompleteContainer;
+
+fn setup_auto_complete_button(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(12.0),
+                bottom: Val::Px(12.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            AutoCompleteContainer,
+        ))
+        .with_children(|parent| {
+            spawn_button(parent, "Auto-complete (F)", AutoCompleteButton);
+        });
+}
+
+fn update_auto_complete_button(
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    state: Res<State<AppState>>,
+    running: Option<Res<AutoComplete>>,
+    mut container: Query<&mut Visibility, With<AutoCompleteContainer>>,
+) {
+    let game = board_state.to_game_state(&deck);
+    let available = *state.get() == AppState::Playing
+        && running.is_none()
+        && game.is_fully_revealed()
+        && !game.is_won();
+
+    for mut visibility in container.iter_mut() {
+        visibility.set_if_neq(if available {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn start_auto_complete(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut auto_move_events: EventReader<AutoMoveEvent>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<AutoCompleteButton>)>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    running: Option<Res<AutoComplete>>,
+) {
+    if running.is_some() {
+        auto_move_events.clear();
+        return;
+    }
+
+    let requested =
+        keys.just_pressed(KeyCode::KeyF) || buttons.iter().any(|i| *i == Interaction::Pressed);
+    let safe_only =
+        mouse.just_pressed(MouseButton::Right) | auto_move_events.read().last().is_some();
+    if safe_only || (requested && board_state.to_game_state(&deck).is_fully_revealed()) {
+        commands.insert_resource(AutoComplete {
+            timer: Timer::from_seconds(AUTO_COMPLETE_INTERVAL, TimerMode::Repeating),
+            safe_only,
+        });
+    }
+}
+
+fn run_auto_complete(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut auto_complete: ResMut<AutoComplete>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    mut move_writer: EventWriter<MoveEvent>,
+) {
+    if !auto_complete.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let game = board_state.to_game_state(&deck);
+    let next = if auto_complete.safe_only {
+        game.next_safe_foundation_move()
+    } else {
+        game.next_foundation_move()
+            .filter(|_| game.is_fully_revealed())
+    };
+    match next {
+        Some(mv) => {
+            move_writer.send(MoveEvent(mv));
+        }
+        None => commands.remove_resource::<AutoComplete>(),
+    }
+}
+
+/// Leaving the game, even just to pause it, or dealing a new one ends any run
+/// of automatic moves.
+fn stop_auto_complete(mut commands: Commands) {
+    commands.remove_resource::<AutoComplete>();
+}
+
+/// Sends a double-clicked waste or tableau top card to a foundation it fits on.
+fn send_card_home(
+    mut commands: Commands,
+    mut events: EventReader<DoubleClickEvent>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    cards: Query<&Card>,
+    dragging: Query<Entity, With<Dragging>>,
+    mut move_writer: EventWriter<MoveEvent>,
+) {
+    let game = board_state.to_game_state(&deck);
+    for DoubleClickEvent(entity) in events.read() {
+        let Ok(card) = cards.get(*entity) else {
+            continue;
+        };
+        let Some(from) = find_source(&board_state, &deck, card) else {
+            continue;
+        };
+        let is_top = game
+            .top_cards(from, 1)
+            .is_some_and(|top| top[0].matches(card));
+        let Some(mv) = game.home_move(from).filter(|_| is_top) else {
+            continue;
+        };
+
+        // The second click also picked the card up; let the move place it instead.
+        for entity in dragging.iter() {
+            commands.entity(entity).remove::<Dragging>();
+        }
+        move_writer.send(MoveEvent(mv));
+        return;
+    }
+}
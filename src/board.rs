@@ -1,18 +1,28 @@
 use bevy::prelude::*;
 
-use crate::card::{CARD_HEIGHT, CARD_SCALE, CARD_WIDTH, Card};
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::layout::Layout;
+use crate::rules::{Column, FOUNDATION_COUNT, GameState, Pile, TABLEAU_COUNT};
+use crate::state::AppState;
 use crate::utils::hovering::{HoverState, Hoverable};
+use bevy::window::{WindowMode, WindowResized};
+use serde::{Deserialize, Serialize};
+
+const BACKGROUND_SIZE: Vec2 = Vec2::new(1920.0, 1080.0);
 
 pub struct BoardPlugin;
 
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<BoardState>()
-            .add_systems(Startup, (setup_background, setup_slots));
+            .add_systems(Startup, setup_background)
+            .add_systems(OnExit(AppState::Loading), setup_slots)
+            .add_systems(Update, (handle_window_resize, toggle_fullscreen));
     }
 }
 
-#[derive(Resource, PartialEq, Debug)]
+#[derive(Resource, Serialize, Deserialize, PartialEq, Clone, Debug)]
 pub struct BoardState {
     pub home_piles: Vec<Vec<Card>>,
     pub play_piles: Vec<Vec<Card>>,
@@ -21,18 +31,52 @@ pub struct BoardState {
 impl Default for BoardState {
     fn default() -> Self {
         BoardState {
-            home_piles: (0..4).map(|_| Vec::new()).collect(),
-            play_piles: (0..7).map(|_| Vec::new()).collect(),
+            home_piles: (0..FOUNDATION_COUNT).map(|_| Vec::new()).collect(),
+            play_piles: (0..TABLEAU_COUNT).map(|_| Vec::new()).collect(),
+        }
+    }
+}
+
+impl BoardState {
+    /// Snapshot of the board and `deck` for the headless rules engine.
+    pub fn to_game_state(&self, deck: &Deck) -> GameState {
+        let turned = |cards: &Vec<Card>, flipped: bool| {
+            cards
+                .iter()
+                .map(|card| Card {
+                    flipped,
+                    ..card.clone()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        GameState {
+            stock: turned(deck.get_cards(), true),
+            waste: turned(deck.get_drawn_cards(), false),
+            tableau: self
+                .play_piles
+                .iter()
+                .map(|pile| {
+                    let (face_down, face_up) = pile.iter().cloned().partition(|c| c.flipped);
+                    Column { face_down, face_up }
+                })
+                .collect(),
+            foundations: self.home_piles.clone(),
+            draw_mode: deck.get_draw_mode(),
         }
     }
 }
 
+#[derive(Component)]
+struct Background;
+
 #[derive(Component, PartialEq)]
 pub struct DeckPosition;
 
 #[derive(Component, PartialEq)]
 pub struct DrawPosition;
 
+/// Foundation marker; the index points straight into `BoardState::home_piles`.
 #[derive(Component, PartialEq)]
 pub struct Home(pub u32);
 
@@ -52,88 +96,129 @@ fn setup_background(mut commands: Commands, asset_server: Res<AssetServer>) {
         },
         Transform::from_xyz(0.0, 0.0, -2.0),
         GlobalTransform::default(),
+        Background,
     ));
 }
 
-fn setup_slots(mut commands: Commands, windows: Query<&Window>, asset_server: Res<AssetServer>) {
+fn setup_slots(
+    mut commands: Commands,
+    windows: Query<&Window>,
+    asset_server: Res<AssetServer>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    mut layout: ResMut<Layout>,
+) {
     let window = windows.single();
-    let window_width = window.width();
-    let window_height = window.height();
-
-    let slot_count = 7;
-    let slots_width = window_width * 0.8;
-    let spacing = slots_width / (slot_count + 1) as f32;
-    let top_row_y = (window_height / 2.0) - (CARD_HEIGHT * CARD_SCALE);
-    let bottom_row_y = top_row_y - (CARD_HEIGHT * CARD_SCALE * 1.5);
+    layout.viewport = window.size();
+    let card_size = layout.card_size();
+    let arrangement = layout.arrange(&board_state.to_game_state(&deck));
 
     let texture_handle = asset_server.load("slot.png");
-
-    for i in 0..slot_count {
-        let x = -slots_width / 2.0 + spacing * (i + 1) as f32;
-
+    let slot_sprite = || Sprite {
+        custom_size: Some(card_size),
+        image: texture_handle.clone(),
+        ..default()
+    };
+    let transform = |pile| arrangement.slot(pile);
+
+    for i in 0..TABLEAU_COUNT {
+        commands.spawn((
+            slot_sprite(),
+            transform(Pile::Tableau(i)),
+            Slot,
+            Col(i as u32),
+            Hoverable,
+            HoverState::default(),
+            GlobalTransform::default(),
+        ));
+    }
+    commands.spawn((
+        slot_sprite(),
+        transform(Pile::Stock),
+        Slot,
+        DeckPosition,
+        Hoverable,
+        HoverState::default(),
+        GlobalTransform::default(),
+    ));
+    commands.spawn((
+        transform(Pile::Waste),
+        Slot,
+        DrawPosition,
+        GlobalTransform::default(),
+    ));
+    for i in 0..FOUNDATION_COUNT {
         commands.spawn((
-            Sprite {
-                custom_size: Some(Vec2 {
-                    x: CARD_WIDTH * CARD_SCALE,
-                    y: CARD_HEIGHT * CARD_SCALE,
-                }),
-                image: texture_handle.clone(),
-                ..default()
-            },
-            Transform::from_xyz(x, bottom_row_y, -1.0),
+            slot_sprite(),
+            transform(Pile::Foundation(i)),
             Slot,
-            Col(i),
+            Home(i as u32),
             Hoverable,
             HoverState::default(),
             GlobalTransform::default(),
         ));
+    }
+}
+
+/// Works the layout out again for the new window size and moves the slots
+/// and background to match; cards follow from `Layout` changing.
+fn handle_window_resize(
+    mut events: EventReader<WindowResized>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    mut layout: ResMut<Layout>,
+    mut slots: Query<(&mut Transform, Option<&mut Sprite>, SlotPile), With<Slot>>,
+    mut background: Query<&mut Sprite, (With<Background>, Without<Slot>)>,
+) {
+    let Some(resized) = events.read().last() else {
+        return;
+    };
+    if resized.width <= 0.0 || resized.height <= 0.0 {
+        return;
+    }
 
-        match i {
-            0 => {
-                commands.spawn((
-                    Sprite {
-                        custom_size: Some(Vec2 {
-                            x: CARD_WIDTH * CARD_SCALE,
-                            y: CARD_HEIGHT * CARD_SCALE,
-                        }),
-                        image: texture_handle.clone(),
-                        ..default()
-                    },
-                    Transform::from_xyz(x, top_row_y, -1.0),
-                    Slot,
-                    DeckPosition,
-                    Hoverable,
-                    HoverState::default(),
-                    GlobalTransform::default(),
-                ));
-            }
-            1 => {
-                commands.spawn((
-                    Transform::from_xyz(x, top_row_y, -1.0),
-                    Slot,
-                    DrawPosition,
-                    GlobalTransform::default(),
-                ));
-            }
-            3..=6 => {
-                commands.spawn((
-                    Sprite {
-                        custom_size: Some(Vec2 {
-                            x: CARD_WIDTH * CARD_SCALE,
-                            y: CARD_HEIGHT * CARD_SCALE,
-                        }),
-                        image: texture_handle.clone(),
-                        ..default()
-                    },
-                    Transform::from_xyz(x, top_row_y, -1.0),
-                    Slot,
-                    Home(i),
-                    Hoverable,
-                    HoverState::default(),
-                    GlobalTransform::default(),
-                ));
-            }
-            _ => (),
+    layout.viewport = Vec2::new(resized.width, resized.height);
+    let arrangement = layout.arrange(&board_state.to_game_state(&deck));
+    for (mut transform, sprite, pile) in slots.iter_mut() {
+        if let Some(pile) = slot_pile(pile) {
+            transform.translation = arrangement.slot(pile).translation;
+        }
+        if let Some(mut sprite) = sprite {
+            sprite.custom_size = Some(layout.card_size());
         }
     }
+
+    let cover = (resized.width / BACKGROUND_SIZE.x).max(resized.height / BACKGROUND_SIZE.y);
+    for mut sprite in background.iter_mut() {
+        sprite.custom_size = Some(BACKGROUND_SIZE * cover);
+    }
+}
+
+type SlotPile = (
+    Option<&'static Col>,
+    Option<&'static Home>,
+    Has<DeckPosition>,
+    Has<DrawPosition>,
+);
+
+fn slot_pile((col, home, deck, draw): (Option<&Col>, Option<&Home>, bool, bool)) -> Option<Pile> {
+    match (col, home) {
+        (Some(col), _) => Some(Pile::Tableau(col.0 as usize)),
+        (_, Some(home)) => Some(Pile::Foundation(home.0 as usize)),
+        _ if deck => Some(Pile::Stock),
+        _ if draw => Some(Pile::Waste),
+        _ => None,
+    }
+}
+
+fn toggle_fullscreen(keys: Res<ButtonInput<KeyCode>>, mut windows: Query<&mut Window>) {
+    if !keys.just_pressed(KeyCode::F11) {
+        return;
+    }
+
+    let mut window = windows.single_mut();
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        _ => WindowMode::Windowed,
+    };
 }
@@ -1,43 +1,50 @@
-use crate::board::{BoardState, Col, DeckPosition, DrawPosition, Slot};
+use crate::board::{BoardState, Col, DeckPosition, Slot};
 use crate::deck::Deck;
-use crate::events::{HoverEnterEvent, HoverExitEvent};
-use crate::types::{
-    CardFilter, CardHoverItem, DeckCardFilter, DeckSlotFilter, DrawCardFilter, DrawSlotFilter,
-};
+use crate::events::{HoverEnterEvent, HoverExitEvent, MoveEvent, NewGameEvent};
+use crate::history::History;
+use crate::layout::Layout;
+use crate::rules::Move;
+use crate::state::AppState;
+use crate::storage;
+use crate::types::{CardFilter, CardSimpleHoverItem, DeckCardFilter, DeckSlotFilter};
 use crate::utils::dragging::Draggable;
-use crate::utils::flipping::Flipping;
 use crate::utils::hovering::{HoverState, Hoverable};
-use crate::utils::moveto::MoveTo;
-use bevy::asset::LoadState;
 use bevy::prelude::*;
-use std::f32::consts::PI;
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 pub const CARD_WIDTH: f32 = 352.0;
 pub const CARD_HEIGHT: f32 = 512.0;
-pub const CARD_SCALE: f32 = 0.2;
+const CARD_BACK_FILE: &str = "card_back.ron";
 
 pub struct CardPlugin;
 
 impl Plugin for CardPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Deck>()
-            .init_resource::<AssetsLoading>()
-            .add_systems(Startup, init_load_card_assets)
-            .add_systems(PostStartup, (setup_cards, setup_deck_cards).chain())
+            .insert_resource(CardBack::load())
             .add_systems(
                 Update,
                 (
-                    check_assets_ready.run_if(resource_exists::<AssetsLoading>),
                     handle_hover_enter,
+                    apply_card_back.run_if(resource_changed::<CardBack>),
+                    resize_cards.run_if(resource_changed::<Layout>),
                     handle_hover_exit,
-                    handle_deck_click,
+                    handle_deck_click.run_if(in_state(AppState::Playing)),
+                    (
+                        deal_new_game,
+                        (setup_cards, setup_deck_cards)
+                            .chain()
+                            .run_if(on_event::<NewGameEvent>),
+                    )
+                        .chain()
+                        .run_if(not(in_state(AppState::Loading))),
                 ),
             );
     }
 }
 
-#[derive(PartialEq, PartialOrd, Debug, Clone, EnumIter)]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Debug, Clone, EnumIter, Serialize, Deserialize)]
 pub enum Suit {
     Clubs,
     Diamonds,
@@ -45,7 +52,13 @@ pub enum Suit {
     Spades,
 }
 
-#[derive(PartialEq, PartialOrd, Debug, Clone, EnumIter)]
+impl Suit {
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Diamonds | Suit::Hearts)
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, PartialOrd, Debug, Clone, EnumIter, Serialize, Deserialize)]
 pub enum Rank {
     Ace,
     Two,
@@ -62,14 +75,38 @@ pub enum Rank {
     King,
 }
 
-#[derive(Component, PartialEq, PartialOrd, Debug, Clone)]
+#[derive(Component, PartialEq, Eq, Hash, PartialOrd, Debug, Clone, Serialize, Deserialize)]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
     pub flipped: bool,
 }
 
+impl Rank {
+    pub fn value(&self) -> u8 {
+        self.clone() as u8 + 1
+    }
+}
+
 impl Card {
+    /// Compares rank and suit only, ignoring which way up the card is.
+    pub fn matches(&self, other: &Card) -> bool {
+        self.rank == other.rank && self.suit == other.suit
+    }
+
+    /// Klondike tableau rule: one rank lower and the opposite colour.
+    pub fn can_stack_on(&self, other: &Card) -> bool {
+        self.rank.value() + 1 == other.rank.value() && self.suit.is_red() != other.suit.is_red()
+    }
+
+    /// Klondike foundation rule: an Ace on an empty pile, otherwise the next rank of the same suit.
+    pub fn can_move_home(&self, top: Option<&Card>) -> bool {
+        match top {
+            None => self.rank == Rank::Ace,
+            Some(top) => self.suit == top.suit && self.rank.value() == top.rank.value() + 1,
+        }
+    }
+
     pub fn asset(&self, asset_server: &Res<AssetServer>) -> Handle<Image> {
         let prefix = match self.suit {
             Suit::Clubs => "clubs".to_string(),
@@ -97,14 +134,43 @@ impl Card {
         let resource_path = format!("{}_{}.png", prefix, suffix);
         asset_server.load(resource_path)
     }
+}
 
-    pub fn back_asset(&self, asset_server: &Res<AssetServer>) -> Handle<Image> {
-        asset_server.load("back01.png")
+/// Which of the bundled `backNN.png` designs face-down cards show.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct CardBack(u8);
+
+impl Default for CardBack {
+    fn default() -> Self {
+        CardBack(1)
     }
 }
 
-#[derive(Resource, Default, Debug)]
-pub struct AssetsLoading(Vec<Handle<Image>>);
+impl CardBack {
+    pub const COUNT: u8 = 8;
+
+    pub fn all() -> impl Iterator<Item = CardBack> {
+        (1..=CardBack::COUNT).map(CardBack)
+    }
+
+    pub fn load() -> Self {
+        storage::load::<CardBack>(CARD_BACK_FILE)
+            .filter(|back| (1..=CardBack::COUNT).contains(&back.0))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(CARD_BACK_FILE, self);
+    }
+
+    pub fn path(&self) -> String {
+        format!("back{:02}.png", self.0)
+    }
+
+    pub fn asset(&self, asset_server: &Res<AssetServer>) -> Handle<Image> {
+        asset_server.load(self.path())
+    }
+}
 
 #[derive(Bundle)]
 pub struct CardBundle {
@@ -114,9 +180,15 @@ pub struct CardBundle {
 }
 
 impl CardBundle {
-    pub fn new(card: &Card, asset_server: &Res<AssetServer>, transform: Transform) -> Self {
+    pub fn new(
+        card: &Card,
+        asset_server: &Res<AssetServer>,
+        back: &CardBack,
+        size: Vec2,
+        transform: Transform,
+    ) -> Self {
         let image = if card.flipped {
-            card.back_asset(asset_server)
+            back.asset(asset_server)
         } else {
             card.asset(asset_server)
         };
@@ -124,10 +196,7 @@ impl CardBundle {
         CardBundle {
             card: card.clone(),
             sprite: Sprite {
-                custom_size: Some(Vec2 {
-                    x: CARD_WIDTH * CARD_SCALE,
-                    y: CARD_HEIGHT * CARD_SCALE,
-                }),
+                custom_size: Some(size),
                 image,
                 ..default()
             },
@@ -136,41 +205,31 @@ impl CardBundle {
     }
 }
 
-fn init_load_card_assets(
-    asset_server: Res<AssetServer>,
-    deck: Res<Deck>,
-    mut loading: ResMut<AssetsLoading>,
-) {
-    for card in deck.get_cards() {
-        let handle = card.asset(&asset_server);
-        loading.0.push(handle);
-    }
-}
-
-fn check_assets_ready(
+#[allow(clippy::too_many_arguments)]
+fn deal_new_game(
     mut commands: Commands,
-    deck: Res<Deck>,
-    server: Res<AssetServer>,
-    loading: Res<AssetsLoading>,
+    mut events: EventReader<NewGameEvent>,
+    mut deck: ResMut<Deck>,
+    mut board_state: ResMut<BoardState>,
+    mut history: ResMut<History>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    cards: Query<Entity, (With<Card>, Without<Slot>)>,
 ) {
-    let mut load_count = 0;
+    let Some(event) = events.read().last() else {
+        return;
+    };
 
-    for asset_handle in loading.0.iter() {
-        let load_state = server.get_load_state(asset_handle.id());
-        match load_state {
-            Some(LoadState::Loaded) => {
-                load_count += 1;
-            }
-            Some(LoadState::Failed(error)) => {
-                warn!("Failed to load asset: {}", error.to_string());
-            }
-            _ => (),
-        }
+    for entity in cards.iter() {
+        commands.entity(entity).despawn();
     }
-
-    if load_count == deck.get_cards().len() {
-        println!("Loaded all {} card assets.", load_count);
-        commands.remove_resource::<AssetsLoading>();
+    let seed = event.seed.unwrap_or_else(Deck::random_seed);
+    *deck = Deck::with_seed_and_mode(seed, event.draw_mode);
+    *board_state = BoardState::default();
+    history.clear();
+    // A replay deals its game itself and carries on in its own state.
+    if *state.get() != AppState::Replay {
+        next_state.set(AppState::Playing);
     }
 }
 
@@ -179,60 +238,55 @@ fn setup_cards(
     mut board_state: ResMut<BoardState>,
     mut deck: ResMut<Deck>,
     server: Res<AssetServer>,
-    slots: Query<(&Transform, &Col), With<Slot>>,
+    back: Res<CardBack>,
+    layout: Res<Layout>,
 ) {
     let play_piles = &mut board_state.play_piles;
-    let mut target_positions = vec![Vec3::default(); play_piles.len()];
-
-    for (slot_transform, slot) in slots.iter() {
-        let index = slot.0 as usize;
-        target_positions[index] = slot_transform.translation;
-    }
 
     for i in 0..play_piles.len() {
-        let y_offset = (i * 40) as f32;
-        for j in i..play_piles.len() {
+        for (j, pile) in play_piles.iter_mut().enumerate().skip(i) {
             let Some(mut drawn_card) = deck.play() else {
-                return;
+                break;
             };
 
             if j == i {
                 drawn_card.flipped = false
             }
 
-            let transform = Transform::from_xyz(
-                target_positions[j].x,
-                target_positions[j].y - y_offset,
-                i as f32,
-            );
+            pile.push(drawn_card);
+        }
+    }
 
-            commands.spawn((
-                CardBundle::new(&drawn_card, &server, transform),
+    let arrangement = layout.arrange(&board_state.to_game_state(&deck));
+    for (j, pile) in board_state.play_piles.iter().enumerate() {
+        for card in pile {
+            let transform = arrangement.card(card).unwrap_or_default();
+            let mut entity = commands.spawn((
+                CardBundle::new(card, &server, &back, layout.card_size(), transform),
                 Hoverable,
                 HoverState::default(),
                 Col(j as u32),
-                Draggable,
             ));
-
-            play_piles[j].push(drawn_card);
+            if !card.flipped {
+                entity.insert(Draggable);
+            }
         }
     }
 }
 
 fn setup_deck_cards(
     mut commands: Commands,
+    board_state: Res<BoardState>,
     deck: Res<Deck>,
     server: Res<AssetServer>,
-    slots: Query<&Transform, DeckSlotFilter>,
+    back: Res<CardBack>,
+    layout: Res<Layout>,
 ) {
-    let deck_position = slots.single().translation;
-
-    let cards = deck.get_cards();
-    for (i, card) in cards.iter().enumerate() {
-        let transform = Transform::from_xyz(deck_position.x, deck_position.y, i as f32);
-
+    let arrangement = layout.arrange(&board_state.to_game_state(&deck));
+    for card in deck.get_cards() {
+        let transform = arrangement.card(card).unwrap_or_default();
         commands.spawn((
-            CardBundle::new(card, &server, transform),
+            CardBundle::new(card, &server, &back, layout.card_size(), transform),
             Hoverable,
             HoverState::default(),
             DeckPosition,
@@ -240,79 +294,46 @@ fn setup_deck_cards(
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Swaps the picture on every card already face down, stock included.
+fn apply_card_back(
+    back: Res<CardBack>,
+    server: Res<AssetServer>,
+    mut cards: Query<(&Card, &mut Sprite)>,
+) {
+    let image = back.asset(&server);
+    for (card, mut sprite) in cards.iter_mut() {
+        if card.flipped {
+            sprite.image = image.clone();
+        }
+    }
+}
+
+fn resize_cards(layout: Res<Layout>, mut cards: Query<&mut Sprite, With<Card>>) {
+    for mut sprite in cards.iter_mut() {
+        sprite.custom_size = Some(layout.card_size());
+    }
+}
+
 fn handle_deck_click(
-    mut commands: Commands,
-    mut deck: ResMut<Deck>,
-    deck_slot: Query<(&Transform, &HoverState), DeckSlotFilter>,
-    draw_slot: Query<&Transform, DrawSlotFilter>,
+    deck: Res<Deck>,
+    deck_slot: Query<&HoverState, DeckSlotFilter>,
     input: Res<ButtonInput<MouseButton>>,
-    mut deck_card_q: Query<CardHoverItem, DeckCardFilter>,
-    mut draw_card_q: Query<CardHoverItem, DrawCardFilter>,
+    mut deck_card_q: Query<CardSimpleHoverItem, DeckCardFilter>,
     mut hover_exit_writer: EventWriter<HoverExitEvent>,
-    server: Res<AssetServer>,
+    mut move_writer: EventWriter<MoveEvent>,
 ) {
     if input.just_pressed(MouseButton::Left) {
         let reset_deck = deck.is_empty() && !deck.get_drawn_cards().is_empty();
         if reset_deck {
-            let (transform, hover_state) = deck_slot.single();
-            let deck_position = transform.translation;
-            if !hover_state.hovering {
-                return;
-            }
-            for (entity, mut transform, mut sprite, _, mut card) in draw_card_q.iter_mut() {
-                let current_z = transform.translation.z;
-                transform.translation = Vec3::new(
-                    deck_position.x,
-                    deck_position.y,
-                    deck.get_drawn_cards().len() as f32 - current_z,
-                );
-
-                commands
-                    .entity(entity)
-                    .remove::<DrawPosition>()
-                    .remove::<MoveTo>()
-                    .remove::<Draggable>()
-                    .insert(DeckPosition);
-                card.flipped = true;
-                sprite.image = card.back_asset(&server);
+            if deck_slot.single().hovering {
+                move_writer.send(MoveEvent(Move::Recycle));
             }
-            deck.reset();
         } else {
-            for (entity, mut transform, _, mut hover_state, card) in deck_card_q.iter_mut() {
+            for (entity, mut hover_state) in deck_card_q.iter_mut() {
                 if hover_state.hovering {
-                    let Some(top_card) = deck.draw() else {
-                        continue;
-                    };
-                    if top_card != *card {
-                        continue;
-                    }
-
                     hover_state.hovering = false;
                     hover_exit_writer.send(HoverExitEvent(entity));
-
-                    let draw_position = draw_slot.single().translation;
-                    transform.translation.z = 100.0;
-                    let target_position = Vec3::new(
-                        draw_position.x,
-                        draw_position.y,
-                        deck.get_drawn_cards().len() as f32,
-                    );
-
-                    commands
-                        .entity(entity)
-                        .remove::<DeckPosition>()
-                        .insert(DrawPosition)
-                        .insert(MoveTo {
-                            target: target_position,
-                            speed: 400.0,
-                        })
-                        .insert(Draggable)
-                        .insert(Flipping {
-                            speed: PI * 3.0,
-                            flipped: false,
-                            progress: 0.0,
-                        });
+                    move_writer.send(MoveEvent(Move::Draw));
                     break;
                 }
             }
@@ -333,10 +354,12 @@ fn handle_hover_enter(
 
 fn handle_hover_exit(
     mut events: EventReader<HoverExitEvent>,
-    mut query: Query<&mut Transform, CardFilter>,
+    mut query: Query<(&mut Transform, &HoverState), CardFilter>,
 ) {
     for HoverExitEvent(entity) in events.read() {
-        if let Ok(mut transform) = query.get_mut(*entity) {
+        if let Ok((mut transform, hover_state)) = query.get_mut(*entity)
+            && !hover_state.focused
+        {
             transform.scale = Vec3::splat(1.0);
         }
     }
@@ -0,0 +1,36 @@
+use crate::events::NewGameEvent;
+use crate::state::AppState;
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameClock>().add_systems(
+            Update,
+            (
+                reset_game_clock,
+                tick_game_clock.run_if(in_state(AppState::Playing)),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Time spent playing the current deal, not counting time the window spent
+/// in the background.
+#[derive(Resource, Default, Debug)]
+pub struct GameClock(pub Stopwatch);
+
+fn tick_game_clock(time: Res<Time>, windows: Query<&Window>, mut clock: ResMut<GameClock>) {
+    if windows.single().focused {
+        clock.0.tick(time.delta());
+    }
+}
+
+fn reset_game_clock(mut events: EventReader<NewGameEvent>, mut clock: ResMut<GameClock>) {
+    if events.read().last().is_some() {
+        clock.0.reset();
+    }
+}
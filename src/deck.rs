@@ -1,29 +1,69 @@
 #![allow(dead_code)]
 
 use bevy::prelude::*;
-use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 
 use crate::card::{Card, Rank, Suit};
+use crate::rules::DrawMode;
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
 pub struct Deck {
     cards: Vec<Card>,
     drawn: Vec<Card>,
+    seed: u64,
+    draw_mode: DrawMode,
+    /// Times the drawn pile has been turned back over onto the stock.
+    recycles: usize,
 }
 
 impl Deck {
     pub fn new() -> Self {
+        Deck::with_seed(Deck::random_seed())
+    }
+
+    /// The same seed always deals the same game, on any platform.
+    pub fn with_seed(seed: u64) -> Self {
+        Deck::with_seed_and_mode(seed, DrawMode::default())
+    }
+
+    pub fn with_seed_and_mode(seed: u64, draw_mode: DrawMode) -> Self {
         let mut deck = Deck {
             cards: Deck::populate(),
             drawn: vec![],
+            seed,
+            draw_mode,
+            recycles: 0,
         };
         deck.shuffle();
         deck
     }
 
+    pub fn random_seed() -> u64 {
+        rand::rng().random_range(1..=MAX_RANDOM_SEED)
+    }
+
+    /// Fisher-Yates driven by our own PRNG rather than `rand`, so a seed's
+    /// deal can't change when a dependency does.
     pub fn shuffle(&mut self) {
-        self.cards.shuffle(&mut rand::rng());
+        let mut rng = SplitMix64(self.seed);
+        for i in (1..self.cards.len()).rev() {
+            let j = rng.below(i as u64 + 1) as usize;
+            self.cards.swap(i, j);
+        }
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_draw_mode(&self) -> DrawMode {
+        self.draw_mode
+    }
+
+    pub fn get_recycles(&self) -> usize {
+        self.recycles
     }
 
     pub fn get_cards(&self) -> &Vec<Card> {
@@ -33,7 +73,7 @@ impl Deck {
         &self.drawn
     }
 
-    fn populate() -> Vec<Card> {
+    pub fn populate() -> Vec<Card> {
         let mut cards = Vec::new();
         for suit in Suit::iter() {
             for rank in Rank::iter() {
@@ -63,10 +103,33 @@ impl Deck {
         card
     }
 
+    /// Turns the drawn pile back over so the stock is dealt in the same order again.
     pub fn reset(&mut self) {
         self.cards = self.drawn.clone();
         self.cards.reverse();
         self.drawn.clear();
+        self.recycles += 1;
+    }
+
+    /// Puts the top drawn card back on the stock, reversing `draw`.
+    pub fn undraw(&mut self) -> Option<Card> {
+        let card = self.drawn.pop();
+        if let Some(card) = card.as_ref() {
+            self.cards.push(card.clone());
+        }
+        card
+    }
+
+    /// Turns the stock back over onto the drawn pile, reversing `reset`.
+    pub fn undo_reset(&mut self) {
+        self.drawn = self.cards.clone();
+        self.drawn.reverse();
+        self.cards.clear();
+        self.recycles -= 1;
+    }
+
+    pub fn push_drawn(&mut self, card: Card) {
+        self.drawn.push(card);
     }
 
     pub fn is_empty(&self) -> bool {
@@ -74,8 +137,82 @@ impl Deck {
     }
 }
 
+/// Upper bound for seeds picked at random, so game numbers stay readable.
+pub const MAX_RANDOM_SEED: u64 = 1_000_000;
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
 impl Default for Deck {
     fn default() -> Self {
         Deck::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Short names like `QS` and `TH`, bottom of the stock first.
+    fn deal_order(seed: u64) -> String {
+        let ranks = [
+            'A', '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K',
+        ];
+        let suits = ['C', 'D', 'H', 'S'];
+        let names: Vec<String> = Deck::with_seed(seed)
+            .get_cards()
+            .iter()
+            .map(|card| {
+                format!(
+                    "{}{}",
+                    ranks[card.rank.clone() as usize],
+                    suits[card.suit.clone() as usize]
+                )
+            })
+            .collect();
+        names.join(" ")
+    }
+
+    #[test]
+    fn split_mix_matches_the_reference_sequence() {
+        let mut rng = SplitMix64(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+        assert_eq!(rng.next_u64(), 0x06C4_5D18_8009_454F);
+    }
+
+    /// A game number has to deal the same cards forever, so shared and
+    /// reported games can be played again. If this fails, old seeds now deal
+    /// different games.
+    #[test]
+    fn seeds_always_deal_the_same_game() {
+        assert_eq!(
+            deal_order(1),
+            "9S 7S QH 4S AC 8H QS JH 6C 5H 7D JC 3D 7H QC AS TC 5C 2H 8C 5S KD TD AH 5D AD \
+             2C 9C 4C 2D 6H 3C KS 8S 3H 6S 7C 3S 8D 6D QD 4D 9H KC JD 2S TH JS 9D TS KH 4H"
+        );
+        assert_eq!(
+            deal_order(12345),
+            "QS TH KS 2C 2S 6S QC KD 8D TS AD 5S AH 4S 4H TD 9S 7H 4C 4D 6D KH JD 8H 3C AC \
+             AS 6H 3S 7S TC 5C KC 2H 5D 3H 2D JH 9H 5H 8S 8C QH 9D 7D JS 3D QD 9C 6C JC 7C"
+        );
+        assert_eq!(
+            deal_order(MAX_RANDOM_SEED),
+            "JH 9C AD TH 7D QD TC JC 7C 4H KD 2H 5H 8D KH 4D QC KC 3S 2S QS 6D 7H 7S 5C 5D \
+             KS TS 5S JS JD 2C 8C 3C AS AC 9H 6S AH TD 6C 3D 9S QH 4C 2D 8H 8S 3H 6H 4S 9D"
+        );
+    }
+}
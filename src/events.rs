@@ -1,11 +1,31 @@
+use crate::focus::FocusAction;
+use crate::history::GameAction;
+use crate::replay::Replay;
+use crate::rules::{DrawMode, Move};
+use crate::score::ScoringMode;
 use bevy::prelude::*;
+use std::time::Duration;
 
 pub struct EventPlugin;
 
 impl Plugin for EventPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<HoverEnterEvent>()
-            .add_event::<HoverExitEvent>();
+            .add_event::<HoverExitEvent>()
+            .add_event::<DoubleClickEvent>()
+            .add_event::<MoveEvent>()
+            .add_event::<UndoEvent>()
+            .add_event::<RedoEvent>()
+            .add_event::<ActionAppliedEvent>()
+            .add_event::<RecordingFinished>()
+            .add_event::<HintEvent>()
+            .add_event::<FocusEvent>()
+            .add_event::<ButtonFocusEvent>()
+            .add_event::<AutoMoveEvent>()
+            .add_event::<NewGameEvent>()
+            .add_event::<ResumeGameEvent>()
+            .add_event::<GameWonEvent>()
+            .add_event::<ScoreChanged>();
     }
 }
 
@@ -13,3 +33,57 @@ impl Plugin for EventPlugin {
 pub struct HoverEnterEvent(pub Entity);
 #[derive(Event)]
 pub struct HoverExitEvent(pub Entity);
+/// The same card was clicked twice in quick succession.
+#[derive(Event)]
+pub struct DoubleClickEvent(pub Entity);
+
+/// Request to play a move; ignored if the rules reject it.
+#[derive(Event)]
+pub struct MoveEvent(pub Move);
+#[derive(Event)]
+pub struct UndoEvent;
+#[derive(Event)]
+pub struct RedoEvent;
+/// A move, undo or redo went through and changed the game.
+#[derive(Event)]
+pub struct ActionAppliedEvent(pub GameAction);
+/// A game was won or abandoned, closing its replay.
+#[derive(Event)]
+pub struct RecordingFinished(pub Replay);
+/// Ask for the next hint; repeated requests cycle through the alternatives.
+#[derive(Event)]
+pub struct HintEvent;
+/// Move the focus cursor, or pick up or put down cards with it.
+#[derive(Event)]
+pub struct FocusEvent(pub FocusAction);
+/// Move between menu buttons, or press the focused one.
+#[derive(Event)]
+pub struct ButtonFocusEvent(pub FocusAction);
+/// Send every card that can safely go home to the foundations.
+#[derive(Event)]
+pub struct AutoMoveEvent;
+/// Deal a fresh game, from the given seed or a random one.
+#[derive(Event)]
+pub struct NewGameEvent {
+    pub seed: Option<u64>,
+    pub draw_mode: DrawMode,
+}
+
+/// Pick up the game saved last session.
+#[derive(Event)]
+pub struct ResumeGameEvent;
+
+#[derive(Event, Debug, Clone)]
+pub struct GameWonEvent {
+    pub elapsed: Duration,
+    pub moves: usize,
+    pub seed: u64,
+    pub draw_mode: DrawMode,
+}
+
+/// The current deal's score went up or down.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScoreChanged {
+    pub points: i32,
+    pub mode: ScoringMode,
+}
@@ -0,0 +1,699 @@
+use crate::board::{BoardState, Col, DeckPosition, Home, Slot};
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::events::{
+    AutoMoveEvent, ButtonFocusEvent, FocusEvent, HintEvent, HoverEnterEvent, HoverExitEvent,
+    MoveEvent, UndoEvent,
+};
+use crate::hud::GameEntry;
+use crate::menu::toggle_pause;
+use crate::rules::{FOUNDATION_COUNT, GameState, Move, Pile, TABLEAU_COUNT};
+use crate::state::AppState;
+use crate::utils::button::CancelButton;
+use crate::utils::hovering::HoverState;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+const FOCUS_COLOUR: Color = Color::srgb(0.5, 0.8, 1.0);
+const BUTTON_FOCUS_WIDTH: f32 = 3.0;
+/// How far a stick has to be pushed before it counts as a direction.
+const STICK_THRESHOLD: f32 = 0.5;
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>()
+            .init_resource::<ButtonFocus>()
+            .add_systems(
+                Update,
+                (
+                    ((keyboard_focus_input, gamepad_focus_input), handle_focus)
+                        .chain()
+                        .after(toggle_pause)
+                        .run_if(in_state(AppState::Playing)),
+                    clamp_focus.run_if(resource_changed::<BoardState>.or(resource_changed::<Deck>)),
+                    show_focus.run_if(
+                        resource_changed::<Focus>
+                            .or(resource_changed::<BoardState>)
+                            .or(resource_changed::<Deck>),
+                    ),
+                )
+                    .chain(),
+            )
+            // Right after bevy_ui has worked out the mouse's interactions, so a
+            // button pressed here is seen by the menus in the same frame.
+            .add_systems(
+                PreUpdate,
+                (
+                    release_focused_button,
+                    (
+                        (keyboard_button_input, gamepad_button_input),
+                        handle_button_focus,
+                    )
+                        .chain()
+                        .run_if(
+                            not(in_state(AppState::Playing)).and(not(in_state(AppState::Replay))),
+                        ),
+                    show_button_focus.run_if(resource_changed::<ButtonFocus>),
+                )
+                    .chain()
+                    .after(UiSystem::Focus),
+            );
+    }
+}
+
+/// A step of focus-driven play, from the keyboard or a gamepad.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FocusAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Pick up the focused cards, or put down the held ones.
+    Select,
+    /// Put the held cards back without moving them.
+    Cancel,
+}
+
+/// A pile and how many cards down from its top the cursor is.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Spot {
+    pub pile: Pile,
+    pub count: usize,
+}
+
+impl Spot {
+    fn top(pile: Pile) -> Self {
+        Spot { pile, count: 1 }
+    }
+}
+
+/// The cursor for playing without a mouse. Nothing is focused until the
+/// first focus input, so mouse players never see it.
+#[derive(Resource, Default, Debug)]
+pub struct Focus {
+    spot: Option<Spot>,
+    /// Cards picked up and waiting for a destination.
+    held: Option<Spot>,
+    /// Entities currently lit up for the cursor and held cards.
+    highlighted: Vec<Entity>,
+}
+
+impl Focus {
+    pub fn is_holding(&self) -> bool {
+        self.held.is_some()
+    }
+}
+
+/// The cursor over menu buttons. As with [`Focus`], nothing is focused until
+/// the first focus input.
+#[derive(Resource, Default, Debug)]
+pub struct ButtonFocus {
+    focused: Option<Entity>,
+    /// Where the cursor is in the menu, so it stays put when the menu is rebuilt.
+    index: usize,
+    /// The button pressed last frame, which bevy_ui won't release on its own.
+    pressed: Option<Entity>,
+}
+
+/// The stock, waste and foundations in the order they sit across the top.
+fn top_row() -> Vec<Pile> {
+    let mut row = vec![Pile::Stock, Pile::Waste];
+    row.extend((0..FOUNDATION_COUNT).map(Pile::Foundation));
+    row
+}
+
+/// The table column a pile sits in, counting the gap after the waste.
+fn column_of(pile: Pile) -> usize {
+    match pile {
+        Pile::Stock => 0,
+        Pile::Waste => 1,
+        Pile::Foundation(i) => i + 3,
+        Pile::Tableau(i) => i,
+    }
+}
+
+fn face_up_len(game: &GameState, pile: Pile) -> usize {
+    match pile {
+        Pile::Tableau(i) => game.tableau[i].face_up.len(),
+        _ => 1,
+    }
+}
+
+fn step(spot: Spot, action: FocusAction, game: &GameState) -> Spot {
+    let row = top_row();
+    match (spot.pile, action) {
+        (Pile::Tableau(_), FocusAction::Up) if spot.count < face_up_len(game, spot.pile) => Spot {
+            count: spot.count + 1,
+            ..spot
+        },
+        (Pile::Tableau(i), FocusAction::Up) => {
+            let above = row
+                .iter()
+                .rev()
+                .find(|pile| column_of(**pile) <= i)
+                .copied()
+                .unwrap_or(Pile::Stock);
+            Spot::top(above)
+        }
+        (Pile::Tableau(_), FocusAction::Down) => Spot {
+            count: spot.count.saturating_sub(1).max(1),
+            ..spot
+        },
+        (Pile::Tableau(i), FocusAction::Left) => Spot::top(Pile::Tableau(i.saturating_sub(1))),
+        (Pile::Tableau(i), FocusAction::Right) => {
+            Spot::top(Pile::Tableau((i + 1).min(TABLEAU_COUNT - 1)))
+        }
+        (pile, FocusAction::Down) => Spot::top(Pile::Tableau(column_of(pile))),
+        (pile, FocusAction::Left | FocusAction::Right) => {
+            let at = row.iter().position(|p| *p == pile).unwrap_or_default();
+            let next = match action {
+                FocusAction::Left => at.saturating_sub(1),
+                _ => (at + 1).min(row.len() - 1),
+            };
+            Spot::top(row[next])
+        }
+        _ => spot,
+    }
+}
+
+fn keyboard_focus_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    entry: Res<GameEntry>,
+    focus: Res<Focus>,
+    deck: Res<Deck>,
+    mut focus_writer: EventWriter<FocusEvent>,
+    mut move_writer: EventWriter<MoveEvent>,
+    mut auto_move_writer: EventWriter<AutoMoveEvent>,
+) {
+    // Typed digits and Enter belong to the game number prompt while it is open.
+    if entry.is_open() {
+        return;
+    }
+
+    let bindings = [
+        (KeyCode::ArrowUp, FocusAction::Up),
+        (KeyCode::ArrowDown, FocusAction::Down),
+        (KeyCode::ArrowLeft, FocusAction::Left),
+        (KeyCode::ArrowRight, FocusAction::Right),
+        (KeyCode::Enter, FocusAction::Select),
+        (KeyCode::Space, FocusAction::Select),
+    ];
+    for (key, action) in bindings {
+        if keys.just_pressed(key) {
+            focus_writer.send(FocusEvent(action));
+        }
+    }
+    // Escape only cancels a pick-up; otherwise it is left to pause the game.
+    if keys.just_pressed(KeyCode::Escape) && focus.is_holding() {
+        focus_writer.send(FocusEvent(FocusAction::Cancel));
+    }
+
+    if keys.just_pressed(KeyCode::KeyD) {
+        move_writer.send(MoveEvent(draw_move(&deck)));
+    }
+    if keys.just_pressed(KeyCode::KeyA) {
+        auto_move_writer.send(AutoMoveEvent);
+    }
+}
+
+/// Turns the stock over, or back over once it has run out.
+fn draw_move(deck: &Deck) -> Move {
+    if deck.is_empty() {
+        Move::Recycle
+    } else {
+        Move::Draw
+    }
+}
+
+/// The direction any left stick has just been pushed in. The stick only
+/// counts once per push, not once per frame it is held.
+fn stick_push(
+    gamepads: &Query<&Gamepad>,
+    last_stick: &mut Option<FocusAction>,
+) -> Option<FocusAction> {
+    let stick = gamepads
+        .iter()
+        .find_map(|gamepad| stick_direction(gamepad.left_stick()));
+    let pushed = stick.filter(|_| *last_stick != stick);
+    *last_stick = stick;
+    pushed
+}
+
+fn stick_direction(stick: Vec2) -> Option<FocusAction> {
+    if stick.length() < STICK_THRESHOLD {
+        None
+    } else if stick.x.abs() > stick.y.abs() {
+        Some(if stick.x > 0.0 {
+            FocusAction::Right
+        } else {
+            FocusAction::Left
+        })
+    } else {
+        Some(if stick.y > 0.0 {
+            FocusAction::Up
+        } else {
+            FocusAction::Down
+        })
+    }
+}
+
+/// South picks up and puts down, East puts back, West draws and North undoes;
+/// either shoulder button cycles through hints.
+#[allow(clippy::too_many_arguments)]
+fn gamepad_focus_input(
+    gamepads: Query<&Gamepad>,
+    focus: Res<Focus>,
+    deck: Res<Deck>,
+    mut last_stick: Local<Option<FocusAction>>,
+    mut focus_writer: EventWriter<FocusEvent>,
+    mut move_writer: EventWriter<MoveEvent>,
+    mut undo_writer: EventWriter<UndoEvent>,
+    mut hint_writer: EventWriter<HintEvent>,
+) {
+    let bindings = [
+        (GamepadButton::DPadUp, FocusAction::Up),
+        (GamepadButton::DPadDown, FocusAction::Down),
+        (GamepadButton::DPadLeft, FocusAction::Left),
+        (GamepadButton::DPadRight, FocusAction::Right),
+        (GamepadButton::South, FocusAction::Select),
+    ];
+    for gamepad in gamepads.iter() {
+        for (button, action) in bindings {
+            if gamepad.just_pressed(button) {
+                focus_writer.send(FocusEvent(action));
+            }
+        }
+        if gamepad.just_pressed(GamepadButton::East) && focus.is_holding() {
+            focus_writer.send(FocusEvent(FocusAction::Cancel));
+        }
+        if gamepad.just_pressed(GamepadButton::West) {
+            move_writer.send(MoveEvent(draw_move(&deck)));
+        }
+        if gamepad.just_pressed(GamepadButton::North) {
+            undo_writer.send(UndoEvent);
+        }
+        if gamepad.any_just_pressed([GamepadButton::LeftTrigger, GamepadButton::RightTrigger]) {
+            hint_writer.send(HintEvent);
+        }
+    }
+    if let Some(action) = stick_push(&gamepads, &mut last_stick) {
+        focus_writer.send(FocusEvent(action));
+    }
+}
+
+fn handle_focus(
+    mut events: EventReader<FocusEvent>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    mut focus: ResMut<Focus>,
+    mut move_writer: EventWriter<MoveEvent>,
+) {
+    let game = board_state.to_game_state(&deck);
+    for FocusEvent(action) in events.read() {
+        let Some(spot) = focus.spot else {
+            // The first press only shows where the cursor is.
+            focus.spot = Some(Spot::top(Pile::Tableau(0)));
+            continue;
+        };
+
+        match (action, focus.held) {
+            (FocusAction::Cancel, _) => focus.held = None,
+            (FocusAction::Select, None) if spot.pile == Pile::Stock => {
+                let mv = if game.stock.is_empty() {
+                    Move::Recycle
+                } else {
+                    Move::Draw
+                };
+                move_writer.send(MoveEvent(mv));
+            }
+            (FocusAction::Select, None) => {
+                let can_lift = game
+                    .top_cards(spot.pile, spot.count)
+                    .is_some_and(|cards| !cards[0].flipped);
+                if can_lift {
+                    focus.held = Some(spot);
+                }
+            }
+            (FocusAction::Select, Some(held)) if held.pile == spot.pile => focus.held = None,
+            (FocusAction::Select, Some(held)) => {
+                let mv = Move::Transfer {
+                    from: held.pile,
+                    to: spot.pile,
+                    count: held.count,
+                };
+                // An illegal drop keeps the cards in hand to try somewhere else.
+                if game.is_legal(mv) {
+                    move_writer.send(MoveEvent(mv));
+                    focus.held = None;
+                    focus.spot = Some(Spot::top(spot.pile));
+                }
+            }
+            (direction, _) => focus.spot = Some(step(spot, *direction, &game)),
+        }
+    }
+}
+
+/// Keeps the cursor on a card that still exists once the piles change.
+fn clamp_focus(board_state: Res<BoardState>, deck: Res<Deck>, mut focus: ResMut<Focus>) {
+    let game = board_state.to_game_state(&deck);
+    let Some(spot) = focus.spot else {
+        return;
+    };
+
+    let count = spot.count.min(face_up_len(&game, spot.pile)).max(1);
+    if count != spot.count {
+        focus.spot = Some(Spot { count, ..spot });
+    }
+    if let Some(held) = focus.held
+        && game.top_cards(held.pile, held.count).is_none()
+    {
+        focus.held = None;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show_focus(
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    mut focus: ResMut<Focus>,
+    mut cards: Query<(Entity, &Card, &mut HoverState), Without<Slot>>,
+    mut slots: Query<
+        (
+            Entity,
+            &mut Sprite,
+            Option<&Col>,
+            Option<&Home>,
+            Has<DeckPosition>,
+        ),
+        With<Slot>,
+    >,
+    mut hover_enter_writer: EventWriter<HoverEnterEvent>,
+    mut hover_exit_writer: EventWriter<HoverExitEvent>,
+) {
+    let focus = focus.bypass_change_detection();
+    for entity in focus.highlighted.drain(..) {
+        if let Ok((.., mut sprite, _, _, _)) = slots.get_mut(entity) {
+            sprite.color = Color::WHITE;
+        } else if let Ok((_, _, mut hover_state)) = cards.get_mut(entity) {
+            hover_state.focused = false;
+            if !hover_state.hovering {
+                hover_exit_writer.send(HoverExitEvent(entity));
+            }
+        }
+    }
+
+    let game = board_state.to_game_state(&deck);
+    let mut lit_cards = vec![];
+    let mut lit_piles = vec![];
+    for spot in [focus.spot, focus.held].into_iter().flatten() {
+        let cards = match spot.pile {
+            Pile::Stock => game.stock.last().map(std::slice::from_ref),
+            pile => game.top_cards(pile, spot.count),
+        };
+        match cards {
+            Some(held) if Some(spot) == focus.held => lit_cards.extend(held.iter().cloned()),
+            Some(cards) => lit_cards.push(cards[0].clone()),
+            None => lit_piles.push(spot.pile),
+        }
+    }
+
+    for (entity, card, mut hover_state) in cards.iter_mut() {
+        if lit_cards.iter().any(|c| c.matches(card)) {
+            hover_state.focused = true;
+            hover_enter_writer.send(HoverEnterEvent(entity));
+            focus.highlighted.push(entity);
+        }
+    }
+    for (entity, mut sprite, col, home, deck) in slots.iter_mut() {
+        let lit = lit_piles.iter().any(|pile| match *pile {
+            Pile::Stock => deck,
+            Pile::Tableau(i) => col.is_some_and(|c| c.0 as usize == i),
+            Pile::Foundation(i) => home.is_some_and(|h| h.0 as usize == i),
+            Pile::Waste => false,
+        });
+        if lit {
+            sprite.color = FOCUS_COLOUR;
+            focus.highlighted.push(entity);
+        }
+    }
+}
+
+/// Arrows move between buttons, Enter or Space presses the focused one and
+/// Escape backs out of the menu.
+fn keyboard_button_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    entry: Res<GameEntry>,
+    mut focus_writer: EventWriter<ButtonFocusEvent>,
+) {
+    // The game number prompt can be open over the Won screen.
+    if entry.is_open() {
+        return;
+    }
+
+    let bindings = [
+        (KeyCode::ArrowUp, FocusAction::Up),
+        (KeyCode::ArrowDown, FocusAction::Down),
+        (KeyCode::ArrowLeft, FocusAction::Left),
+        (KeyCode::ArrowRight, FocusAction::Right),
+        (KeyCode::Enter, FocusAction::Select),
+        (KeyCode::Space, FocusAction::Select),
+        (KeyCode::Escape, FocusAction::Cancel),
+    ];
+    for (key, action) in bindings {
+        if keys.just_pressed(key) {
+            focus_writer.send(ButtonFocusEvent(action));
+        }
+    }
+}
+
+/// The D-pad or stick moves between buttons, South presses the focused one
+/// and East backs out of the menu.
+fn gamepad_button_input(
+    gamepads: Query<&Gamepad>,
+    mut last_stick: Local<Option<FocusAction>>,
+    mut focus_writer: EventWriter<ButtonFocusEvent>,
+) {
+    let bindings = [
+        (GamepadButton::DPadUp, FocusAction::Up),
+        (GamepadButton::DPadDown, FocusAction::Down),
+        (GamepadButton::DPadLeft, FocusAction::Left),
+        (GamepadButton::DPadRight, FocusAction::Right),
+        (GamepadButton::South, FocusAction::Select),
+        (GamepadButton::East, FocusAction::Cancel),
+    ];
+    for gamepad in gamepads.iter() {
+        for (button, action) in bindings {
+            if gamepad.just_pressed(button) {
+                focus_writer.send(ButtonFocusEvent(action));
+            }
+        }
+    }
+    if let Some(action) = stick_push(&gamepads, &mut last_stick) {
+        focus_writer.send(ButtonFocusEvent(action));
+    }
+}
+
+/// The shown buttons of the frontmost menu, top to bottom and left to right.
+/// Each menu sits under a root node whose `GlobalZIndex` puts it in front.
+fn front_menu(
+    buttons: &Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            &mut Interaction,
+            Has<CancelButton>,
+        ),
+        With<Button>,
+    >,
+    ancestors: &Query<&Parent>,
+    layers: &Query<&GlobalZIndex>,
+) -> Vec<(Entity, bool)> {
+    let mut shown: Vec<_> = buttons
+        .iter()
+        .filter(|(_, _, visibility, ..)| visibility.get())
+        .map(|(entity, transform, _, _, cancel)| {
+            let layer = ancestors
+                .iter_ancestors(entity)
+                .find_map(|ancestor| layers.get(ancestor).ok())
+                .map_or(0, |z| z.0);
+            (layer, transform.translation(), entity, cancel)
+        })
+        .collect();
+    let front = shown.iter().map(|(layer, ..)| *layer).max();
+    shown.retain(|(layer, ..)| Some(*layer) == front);
+    shown.sort_by(|(_, a, ..), (_, b, ..)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+    shown
+        .into_iter()
+        .map(|(_, _, entity, cancel)| (entity, cancel))
+        .collect()
+}
+
+fn handle_button_focus(
+    mut events: EventReader<ButtonFocusEvent>,
+    mut focus: ResMut<ButtonFocus>,
+    mut buttons: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ViewVisibility,
+            &mut Interaction,
+            Has<CancelButton>,
+        ),
+        With<Button>,
+    >,
+    ancestors: Query<&Parent>,
+    layers: Query<&GlobalZIndex>,
+) {
+    let menu = front_menu(&buttons, &ancestors, &layers);
+    let Some(last) = menu.len().checked_sub(1) else {
+        events.clear();
+        return;
+    };
+
+    // Follow the cursor, or put it back where it was once its menu is rebuilt
+    // or another opens in front.
+    match focus
+        .focused
+        .map(|f| menu.iter().position(|(e, _)| *e == f))
+    {
+        Some(Some(at)) if at != focus.index => focus.index = at,
+        Some(None) => focus.focused = Some(menu[focus.index.min(last)].0),
+        _ => (),
+    }
+
+    for ButtonFocusEvent(action) in events.read() {
+        let press = match (action, focus.focused) {
+            (FocusAction::Cancel, _) => menu.iter().find(|(_, cancel)| *cancel).map(|(e, _)| *e),
+            // The first press only shows where the cursor is.
+            (_, None) => {
+                focus.index = 0;
+                focus.focused = Some(menu[0].0);
+                None
+            }
+            (FocusAction::Select, focused) => focused,
+            (FocusAction::Up | FocusAction::Left, _) => {
+                focus.index = focus.index.min(last).saturating_sub(1);
+                focus.focused = Some(menu[focus.index].0);
+                None
+            }
+            (FocusAction::Down | FocusAction::Right, _) => {
+                focus.index = (focus.index + 1).min(last);
+                focus.focused = Some(menu[focus.index].0);
+                None
+            }
+        };
+        if let Some(entity) = press
+            && let Ok((.., mut interaction, _)) = buttons.get_mut(entity)
+        {
+            *interaction = Interaction::Pressed;
+            focus.pressed = Some(entity);
+        }
+    }
+}
+
+fn release_focused_button(mut focus: ResMut<ButtonFocus>, mut buttons: Query<&mut Interaction>) {
+    let Some(entity) = focus.pressed else {
+        return;
+    };
+    if let Ok(mut interaction) = buttons.get_mut(entity)
+        && *interaction == Interaction::Pressed
+    {
+        *interaction = Interaction::None;
+    }
+    focus.pressed = None;
+}
+
+fn show_button_focus(
+    mut commands: Commands,
+    focus: Res<ButtonFocus>,
+    outlined: Query<Entity, (With<Button>, With<Outline>)>,
+) {
+    for entity in outlined.iter() {
+        if Some(entity) != focus.focused {
+            commands.entity(entity).remove::<Outline>();
+        }
+    }
+    if let Some(mut entity) = focus.focused.and_then(|e| commands.get_entity(e)) {
+        entity.try_insert(Outline::new(
+            Val::Px(BUTTON_FOCUS_WIDTH),
+            Val::ZERO,
+            FOCUS_COLOUR,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventPlugin;
+
+    fn menu_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, EventPlugin))
+            .init_resource::<ButtonFocus>()
+            .add_systems(
+                Update,
+                (release_focused_button, handle_button_focus).chain(),
+            );
+        app
+    }
+
+    /// A shown button `y` pixels down the screen, under `parent` if given.
+    fn spawn_button_at(app: &mut App, y: f32, parent: Option<Entity>) -> Entity {
+        let mut visibility = ViewVisibility::default();
+        visibility.set();
+        let button = app
+            .world_mut()
+            .spawn((Button, GlobalTransform::from_xyz(0.0, y, 0.0), visibility))
+            .id();
+        if let Some(parent) = parent {
+            app.world_mut().entity_mut(parent).add_child(button);
+        }
+        button
+    }
+
+    fn press(app: &mut App, action: FocusAction) {
+        app.world_mut().send_event(ButtonFocusEvent(action));
+        app.update();
+    }
+
+    fn interaction(app: &App, button: Entity) -> Interaction {
+        *app.world().get::<Interaction>(button).unwrap()
+    }
+
+    #[test]
+    fn select_presses_the_focused_button_for_one_frame() {
+        let mut app = menu_app();
+        let lower = spawn_button_at(&mut app, 200.0, None);
+        let upper = spawn_button_at(&mut app, 100.0, None);
+
+        press(&mut app, FocusAction::Down);
+        assert_eq!(app.world().resource::<ButtonFocus>().focused, Some(upper));
+        press(&mut app, FocusAction::Down);
+        press(&mut app, FocusAction::Select);
+        assert_eq!(interaction(&app, lower), Interaction::Pressed);
+        assert_eq!(interaction(&app, upper), Interaction::None);
+
+        app.update();
+        assert_eq!(interaction(&app, lower), Interaction::None);
+    }
+
+    #[test]
+    fn only_the_front_menu_is_navigated_and_cancelled() {
+        let mut app = menu_app();
+        let behind = spawn_button_at(&mut app, 0.0, None);
+        let panel = app.world_mut().spawn(GlobalZIndex(10)).id();
+        let front = spawn_button_at(&mut app, 100.0, Some(panel));
+        let back = spawn_button_at(&mut app, 200.0, Some(panel));
+        app.world_mut().entity_mut(back).insert(CancelButton);
+
+        press(&mut app, FocusAction::Up);
+        assert_eq!(app.world().resource::<ButtonFocus>().focused, Some(front));
+        press(&mut app, FocusAction::Cancel);
+        assert_eq!(interaction(&app, back), Interaction::Pressed);
+        assert_eq!(interaction(&app, behind), Interaction::None);
+    }
+}
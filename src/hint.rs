@@ -0,0 +1,343 @@
+use crate::board::{BoardState, Col, DeckPosition, Home, Slot};
+use crate::card::{Card, Rank};
+use crate::deck::Deck;
+use crate::events::{HintEvent, HoverEnterEvent, HoverExitEvent};
+use crate::rules::{GameState, Move, Pile};
+use crate::state::AppState;
+use crate::utils::button::spawn_button;
+use bevy::prelude::*;
+
+const HINT_SLOT_COLOUR: Color = Color::srgb(1.0, 0.85, 0.3);
+
+pub struct HintPlugin;
+
+impl Plugin for HintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Hints>()
+            .add_systems(Startup, setup_hint_button)
+            .add_systems(
+                Update,
+                (
+                    update_hint_button,
+                    request_hint.run_if(in_state(AppState::Playing)),
+                    clear_stale_hint,
+                    show_hint,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct Hints {
+    moves: Vec<Move>,
+    index: usize,
+    /// Entities currently lit up for the shown hint.
+    highlighted: Vec<Entity>,
+}
+
+#[derive(Component)]
+struct HintButton;
+
+#[derive(Component)]
+struct HintContainer;
+
+/// Legal moves in the order a hint should suggest them.
+pub fn rank_moves(game: &GameState) -> Vec<Move> {
+    let mut moves: Vec<(u8, Move)> = game
+        .legal_moves()
+        .into_iter()
+        .filter_map(|mv| hint_priority(game, mv).map(|priority| (priority, mv)))
+        .collect();
+    moves.sort_by_key(|(priority, _)| *priority);
+    moves.into_iter().map(|(_, mv)| mv).collect()
+}
+
+fn hint_priority(game: &GameState, mv: Move) -> Option<u8> {
+    let Move::Transfer { from, to, count } = mv else {
+        return Some(6);
+    };
+    let head = &game.top_cards(from, count)?[0];
+
+    match (from, to) {
+        (_, Pile::Foundation(_)) => Some(0),
+        (Pile::Tableau(i), Pile::Tableau(_)) => {
+            let column = &game.tableau[i];
+            let whole_run = count == column.face_up.len();
+            if whole_run && !column.face_down.is_empty() {
+                Some(1)
+            } else if whole_run && head.rank == Rank::King {
+                // Shuffling a King between empty columns achieves nothing.
+                None
+            } else if whole_run {
+                Some(4)
+            } else {
+                Some(5)
+            }
+        }
+        (Pile::Waste, Pile::Tableau(i)) if game.tableau[i].is_empty() => Some(2),
+        (Pile::Waste, _) => Some(3),
+        // Taking a card back off a foundation is a last resort.
+        _ => Some(5),
+    }
+}
+
+fn setup_hint_button(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(12.0),
+                bottom: Val::Px(12.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            HintContainer,
+        ))
+        .with_children(|parent| {
+            spawn_button(parent, "Hint (H)", HintButton);
+        });
+}
+
+fn update_hint_button(
+    state: Res<State<AppState>>,
+    mut container: Query<&mut Visibility, With<HintContainer>>,
+) {
+    for mut visibility in container.iter_mut() {
+        visibility.set_if_neq(if *state.get() == AppState::Playing {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+fn request_hint(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<HintButton>)>,
+    mut hint_writer: EventWriter<HintEvent>,
+) {
+    if keys.just_pressed(KeyCode::KeyH) || buttons.iter().any(|i| *i == Interaction::Pressed) {
+        hint_writer.send(HintEvent);
+    }
+}
+
+/// Drops the shown hint as soon as the board changes under it.
+fn clear_stale_hint(
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    mut hints: ResMut<Hints>,
+    mut slots: Query<&mut Sprite, With<Slot>>,
+    mut hover_exit_writer: EventWriter<HoverExitEvent>,
+) {
+    if !board_state.is_changed() && !deck.is_changed() {
+        return;
+    }
+    hints.moves.clear();
+    clear_highlight(&mut hints, &mut slots, &mut hover_exit_writer);
+}
+
+fn clear_highlight(
+    hints: &mut Hints,
+    slots: &mut Query<&mut Sprite, With<Slot>>,
+    hover_exit_writer: &mut EventWriter<HoverExitEvent>,
+) {
+    for entity in hints.highlighted.drain(..) {
+        if let Ok(mut sprite) = slots.get_mut(entity) {
+            sprite.color = Color::WHITE;
+        } else {
+            hover_exit_writer.send(HoverExitEvent(entity));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show_hint(
+    mut events: EventReader<HintEvent>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    mut hints: ResMut<Hints>,
+    cards: Query<(Entity, &Card), Without<Slot>>,
+    slot_markers: Query<(Entity, Option<&Col>, Option<&Home>, Has<DeckPosition>), With<Slot>>,
+    mut slots: Query<&mut Sprite, With<Slot>>,
+    mut hover_enter_writer: EventWriter<HoverEnterEvent>,
+    mut hover_exit_writer: EventWriter<HoverExitEvent>,
+) {
+    if events.read().last().is_none() {
+        return;
+    }
+
+    let game = board_state.to_game_state(&deck);
+    if hints.moves.is_empty() {
+        hints.moves = rank_moves(&game);
+        hints.index = 0;
+    } else {
+        hints.index = (hints.index + 1) % hints.moves.len();
+    }
+    clear_highlight(&mut hints, &mut slots, &mut hover_exit_writer);
+
+    let Some(&mv) = hints.moves.get(hints.index) else {
+        return;
+    };
+
+    let card_entity = |card: &Card| {
+        cards
+            .iter()
+            .find(|(_, c)| c.matches(card))
+            .map(|(entity, _)| entity)
+    };
+    let slot_entity = |pile: Pile| {
+        slot_markers
+            .iter()
+            .find(|(_, col, home, deck)| match pile {
+                Pile::Stock => *deck,
+                Pile::Tableau(i) => col.is_some_and(|c| c.0 as usize == i),
+                Pile::Foundation(i) => home.is_some_and(|h| h.0 as usize == i),
+                Pile::Waste => false,
+            })
+            .map(|(entity, ..)| entity)
+    };
+
+    let mut cards_lit = vec![];
+    let mut slots_lit = vec![];
+    match mv {
+        Move::Draw => cards_lit.extend(game.stock.last().and_then(card_entity)),
+        Move::Recycle => slots_lit.extend(slot_entity(Pile::Stock)),
+        Move::Transfer { from, to, count } => {
+            if let Some(cards) = game.top_cards(from, count) {
+                cards_lit.extend(card_entity(&cards[0]));
+            }
+            let destination = match to {
+                Pile::Tableau(i) => game.tableau[i].face_up.last(),
+                Pile::Foundation(i) => game.foundations[i].last(),
+                _ => None,
+            };
+            match destination.and_then(card_entity) {
+                Some(entity) => cards_lit.push(entity),
+                None => slots_lit.extend(slot_entity(to)),
+            }
+        }
+    }
+
+    for entity in cards_lit {
+        hover_enter_writer.send(HoverEnterEvent(entity));
+        hints.highlighted.push(entity);
+    }
+    for entity in slots_lit {
+        if let Ok(mut sprite) = slots.get_mut(entity) {
+            sprite.color = HINT_SLOT_COLOUR;
+            hints.highlighted.push(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Suit;
+    use crate::rules::{Column, DrawMode, FOUNDATION_COUNT, TABLEAU_COUNT};
+    use strum::IntoEnumIterator;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card {
+            rank,
+            suit,
+            flipped: false,
+        }
+    }
+
+    fn column(face_down: Vec<Card>, face_up: Vec<Card>) -> Column {
+        let face_down = face_down
+            .into_iter()
+            .map(|card| Card {
+                flipped: true,
+                ..card
+            })
+            .collect();
+        Column { face_down, face_up }
+    }
+
+    fn empty() -> GameState {
+        GameState {
+            stock: vec![],
+            waste: vec![],
+            tableau: (0..TABLEAU_COUNT).map(|_| Column::default()).collect(),
+            foundations: (0..FOUNDATION_COUNT).map(|_| Vec::new()).collect(),
+            draw_mode: DrawMode::One,
+        }
+    }
+
+    fn transfer(from: Pile, to: Pile) -> Move {
+        Move::Transfer { from, to, count: 1 }
+    }
+
+    /// Asserts `moves` are all hinted, in the order given.
+    fn assert_ranked(game: &GameState, moves: &[Move]) {
+        let ranked = rank_moves(game);
+        let positions: Vec<usize> = moves
+            .iter()
+            .map(|mv| {
+                ranked
+                    .iter()
+                    .position(|m| m == mv)
+                    .unwrap_or_else(|| panic!("{mv:?} isn't hinted in {ranked:?}"))
+            })
+            .collect();
+        assert!(
+            positions.is_sorted_by(|a, b| a < b),
+            "hinted out of order: {ranked:?}"
+        );
+    }
+
+    #[test]
+    fn hints_go_home_then_reveal_then_play_the_waste_then_shuffle_the_tableau() {
+        let mut game = empty();
+        game.stock = vec![card(Rank::Five, Suit::Clubs)];
+        game.waste = vec![card(Rank::Two, Suit::Clubs)];
+        game.foundations[0] = vec![
+            card(Rank::Ace, Suit::Diamonds),
+            card(Rank::Two, Suit::Diamonds),
+        ];
+        game.tableau[0] = column(
+            vec![card(Rank::Nine, Suit::Hearts)],
+            vec![card(Rank::Ace, Suit::Hearts)],
+        );
+        game.tableau[1] = column(
+            vec![card(Rank::Nine, Suit::Spades)],
+            vec![card(Rank::Three, Suit::Hearts)],
+        );
+        game.tableau[2] = column(vec![], vec![card(Rank::Three, Suit::Diamonds)]);
+        game.tableau[3] = column(vec![], vec![card(Rank::Four, Suit::Spades)]);
+        game.tableau[4] = column(vec![], vec![card(Rank::Three, Suit::Spades)]);
+
+        assert_ranked(
+            &game,
+            &[
+                transfer(Pile::Tableau(0), Pile::Foundation(1)),
+                transfer(Pile::Tableau(1), Pile::Tableau(3)),
+                transfer(Pile::Waste, Pile::Tableau(1)),
+                transfer(Pile::Tableau(2), Pile::Tableau(3)),
+                transfer(Pile::Foundation(0), Pile::Tableau(4)),
+                Move::Draw,
+            ],
+        );
+    }
+
+    #[test]
+    fn a_king_from_the_waste_fills_an_empty_column_but_one_from_a_foundation_waits() {
+        let mut game = empty();
+        game.waste = vec![card(Rank::King, Suit::Clubs)];
+        game.foundations[0] = Rank::iter().map(|rank| card(rank, Suit::Spades)).collect();
+        game.tableau[0] = column(vec![], vec![card(Rank::Four, Suit::Spades)]);
+        game.tableau[1] = column(vec![], vec![card(Rank::Three, Suit::Diamonds)]);
+
+        assert_ranked(
+            &game,
+            &[
+                transfer(Pile::Waste, Pile::Tableau(2)),
+                transfer(Pile::Tableau(1), Pile::Tableau(0)),
+                transfer(Pile::Foundation(0), Pile::Tableau(2)),
+            ],
+        );
+    }
+}
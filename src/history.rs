@@ -0,0 +1,99 @@
+use crate::events::{RedoEvent, UndoEvent};
+use crate::rules::Move;
+use crate::state::AppState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<History>().add_systems(
+            Update,
+            handle_history_input.run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// Something the player did that changed the game.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum GameAction {
+    Play(Move),
+    Undo,
+    Redo,
+}
+
+impl GameAction {
+    /// The action that takes the game back to how it was before this one.
+    pub fn inverse(self) -> Self {
+        match self {
+            GameAction::Play(_) | GameAction::Redo => GameAction::Undo,
+            GameAction::Undo => GameAction::Redo,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub mv: Move,
+    /// The move left a face-down tableau card exposed and it was turned over.
+    pub revealed: bool,
+    /// Cards turned over by a `Move::Draw`; fewer than the draw mode's count
+    /// when the stock ran short.
+    pub drawn: usize,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Default, Debug)]
+pub struct History {
+    done: Vec<Record>,
+    undone: Vec<Record>,
+}
+
+impl History {
+    pub fn push(&mut self, record: Record) {
+        self.done.push(record);
+        self.undone.clear();
+    }
+
+    pub fn undo(&mut self) -> Option<Record> {
+        let record = self.done.pop()?;
+        self.undone.push(record);
+        Some(record)
+    }
+
+    pub fn redo(&mut self) -> Option<Record> {
+        let record = self.undone.pop()?;
+        self.done.push(record);
+        Some(record)
+    }
+
+    /// Moves played so far, not counting any that were undone.
+    pub fn len(&self) -> usize {
+        self.done.len()
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.done
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+fn handle_history_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut undo_writer: EventWriter<UndoEvent>,
+    mut redo_writer: EventWriter<RedoEvent>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyZ) {
+        undo_writer.send(UndoEvent);
+    } else if keys.just_pressed(KeyCode::KeyY) {
+        redo_writer.send(RedoEvent);
+    }
+}
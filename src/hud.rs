@@ -0,0 +1,272 @@
+use crate::clock::GameClock;
+use crate::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::deck::Deck;
+use crate::events::{NewGameEvent, ScoreChanged};
+use crate::history::History;
+use crate::score::ScoringMode;
+use crate::state::AppState;
+use crate::utils::button::BUTTON_COLOUR;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+
+pub const HUD_FONT_SIZE: f32 = 18.0;
+pub const HUD_TEXT_COLOUR: Color = Color::srgb(0.9, 0.9, 0.9);
+const HUD_BAR_COLOUR: Color = Color::srgba(0.0, 0.0, 0.0, 0.4);
+/// Thin enough to stay clear of the top row of cards.
+const HUD_BAR_HEIGHT: f32 = WINDOW_HEIGHT * 0.05;
+const HUD_BAR_PADDING: f32 = WINDOW_WIDTH * 0.01;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameEntry>()
+            .add_systems(Startup, setup_hud)
+            .add_systems(
+                Update,
+                (
+                    handle_game_entry
+                        .run_if(in_state(AppState::Playing).or(in_state(AppState::Won))),
+                    update_hud_visibility,
+                    update_game_number,
+                    update_clock_text,
+                    update_counters,
+                    update_score_text,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The bar along the top that holds every HUD counter.
+#[derive(Component)]
+struct HudBar;
+
+#[derive(Component)]
+pub struct GameNumberText;
+
+/// The game number doubles as the button that opens the "play game #N" prompt.
+#[derive(Component)]
+struct GameEntryButton;
+
+#[derive(Component)]
+pub struct ClockText;
+
+#[derive(Component)]
+pub struct MovesText;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[derive(Component)]
+pub struct StockText;
+
+#[derive(Component)]
+pub struct RecyclesText;
+
+/// Digits typed so far for "play game #N", while the prompt is open.
+#[derive(Resource, Default, Debug)]
+pub struct GameEntry(Option<String>);
+
+impl GameEntry {
+    pub fn is_open(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+fn setup_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                left: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Px(HUD_BAR_HEIGHT),
+                padding: UiRect::horizontal(Val::Px(HUD_BAR_PADDING)),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(HUD_BAR_COLOUR),
+            Visibility::Hidden,
+            HudBar,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(BUTTON_COLOUR),
+                    GameEntryButton,
+                ))
+                .with_children(|button| spawn_hud_text(button, "", GameNumberText));
+            spawn_hud_text(parent, &clock_label(0), ClockText);
+            spawn_hud_text(parent, "Moves 0", MovesText);
+            spawn_hud_text(parent, &score_label(0, ScoringMode::default()), ScoreText);
+            spawn_hud_text(parent, "", StockText);
+            spawn_hud_text(parent, "", RecyclesText);
+        });
+}
+
+fn spawn_hud_text<T: Component>(parent: &mut ChildBuilder, label: &str, marker: T) {
+    parent.spawn((
+        Text::new(label),
+        TextFont {
+            font_size: HUD_FONT_SIZE,
+            ..default()
+        },
+        TextColor(HUD_TEXT_COLOUR),
+        marker,
+    ));
+}
+
+fn handle_game_entry(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<GameEntryButton>)>,
+    deck: Res<Deck>,
+    mut key_events: EventReader<KeyboardInput>,
+    mut entry: ResMut<GameEntry>,
+    mut new_game_writer: EventWriter<NewGameEvent>,
+) {
+    let Some(digits) = entry.0.as_mut() else {
+        key_events.clear();
+        if keys.just_pressed(KeyCode::KeyG) || buttons.iter().any(|i| *i == Interaction::Pressed) {
+            entry.0 = Some(String::new());
+        } else if keys.just_pressed(KeyCode::KeyN) {
+            new_game_writer.send(NewGameEvent {
+                seed: None,
+                draw_mode: deck.get_draw_mode(),
+            });
+        }
+        return;
+    };
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Character(c) if c.chars().all(|c| c.is_ascii_digit()) => digits.push_str(c),
+            Key::Backspace => {
+                digits.pop();
+            }
+            Key::Enter => {
+                if let Ok(seed) = digits.parse() {
+                    new_game_writer.send(NewGameEvent {
+                        seed: Some(seed),
+                        draw_mode: deck.get_draw_mode(),
+                    });
+                }
+                entry.0 = None;
+                return;
+            }
+            Key::Escape => {
+                entry.0 = None;
+                return;
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Shown while playing, and on the Won screen only to type a game number into.
+fn update_hud_visibility(
+    state: Res<State<AppState>>,
+    entry: Res<GameEntry>,
+    mut bar: Query<&mut Visibility, With<HudBar>>,
+) {
+    let shown = match state.get() {
+        AppState::Playing => true,
+        AppState::Won => entry.is_open(),
+        _ => false,
+    };
+    for mut visibility in bar.iter_mut() {
+        visibility.set_if_neq(if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+fn update_game_number(
+    deck: Res<Deck>,
+    entry: Res<GameEntry>,
+    mut query: Query<&mut Text, With<GameNumberText>>,
+) {
+    if !deck.is_changed() && !entry.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.0 = match &entry.0 {
+            Some(digits) => format!("Play game #{}_", digits),
+            None => format!(
+                "Game #{} (G)  -  Draw {}",
+                deck.get_seed(),
+                deck.get_draw_mode().count()
+            ),
+        };
+    }
+}
+
+fn clock_label(seconds: u64) -> String {
+    format!("Time {}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn update_clock_text(clock: Res<GameClock>, mut query: Query<&mut Text, With<ClockText>>) {
+    let label = clock_label(clock.0.elapsed().as_secs());
+    for mut text in query.iter_mut() {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
+
+fn update_counters(
+    deck: Res<Deck>,
+    history: Res<History>,
+    mut moves_q: Query<&mut Text, (With<MovesText>, Without<StockText>, Without<RecyclesText>)>,
+    mut stock_q: Query<&mut Text, (With<StockText>, Without<RecyclesText>)>,
+    mut recycles_q: Query<&mut Text, With<RecyclesText>>,
+) {
+    if !deck.is_changed() && !history.is_changed() {
+        return;
+    }
+
+    for mut text in moves_q.iter_mut() {
+        text.0 = format!("Moves {}", history.len());
+    }
+    for mut text in stock_q.iter_mut() {
+        text.0 = format!("Stock {}", deck.get_cards().len());
+    }
+    for mut text in recycles_q.iter_mut() {
+        text.0 = format!("Recycled {}", deck.get_recycles());
+    }
+}
+
+fn score_label(points: i32, mode: ScoringMode) -> String {
+    match mode {
+        ScoringMode::Standard => format!("Score {}", points),
+        ScoringMode::Vegas if points < 0 => format!("Vegas -${}", -points),
+        ScoringMode::Vegas => format!("Vegas ${}", points),
+    }
+}
+
+fn update_score_text(
+    mut events: EventReader<ScoreChanged>,
+    mut query: Query<&mut Text, With<ScoreText>>,
+) {
+    let Some(changed) = events.read().last() else {
+        return;
+    };
+
+    for mut text in query.iter_mut() {
+        text.0 = score_label(changed.points, changed.mode);
+    }
+}
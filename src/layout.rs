@@ -0,0 +1,399 @@
+//! Where everything on the table goes.
+//!
+//! `arrange` works out a target transform for every card and slot from the
+//! game, the window size and the layout settings alone. It never touches the
+//! ECS; `drive_cards` is what moves card entities towards its answer whenever
+//! any of those change, so deals, moves, undos and resizes all agree.
+use crate::board::{BoardState, Slot};
+use crate::card::{CARD_HEIGHT, CARD_WIDTH, Card};
+use crate::config::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::deck::Deck;
+use crate::moves::MOVE_SPEED;
+use crate::rules::{FOUNDATION_COUNT, GameState, Pile, TABLEAU_COUNT};
+use crate::utils::dragging::Dragging;
+use crate::utils::moveto::MoveTo;
+use bevy::prelude::*;
+
+pub const CARD_SCALE: f32 = 0.2;
+pub const TABLEAU_FAN_OFFSET: f32 = 40.0;
+pub const FACE_DOWN_FAN_OFFSET: f32 = 15.0;
+pub const WASTE_FAN_OFFSET: f32 = 20.0;
+/// Lifts a card above the rest of the table while it travels.
+const IN_FLIGHT_Z: f32 = 100.0;
+/// Gap left between a column's last card and the bottom of the window.
+const BOTTOM_MARGIN: f32 = 8.0;
+
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Layout>().add_systems(
+            PostUpdate,
+            drive_cards
+                .before(TransformSystem::TransformPropagate)
+                .run_if(
+                    resource_changed::<BoardState>
+                        .or(resource_changed::<Deck>)
+                        .or(resource_changed::<Layout>),
+                ),
+        );
+    }
+}
+
+/// Sizes as they are in the default window; everything scales from these.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LayoutSettings {
+    pub card_scale: f32,
+    /// Step below each face-up tableau card, before any squeezing.
+    pub tableau_fan_offset: f32,
+    /// Step below each face-down tableau card, before any squeezing.
+    pub face_down_fan_offset: f32,
+    pub waste_fan_offset: f32,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        LayoutSettings {
+            card_scale: CARD_SCALE,
+            tableau_fan_offset: TABLEAU_FAN_OFFSET,
+            face_down_fan_offset: FACE_DOWN_FAN_OFFSET,
+            waste_fan_offset: WASTE_FAN_OFFSET,
+        }
+    }
+}
+
+/// The window size and settings the table is currently laid out for.
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct Layout {
+    pub viewport: Vec2,
+    pub settings: LayoutSettings,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::new(Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT))
+    }
+}
+
+impl Layout {
+    pub fn new(viewport: Vec2) -> Self {
+        Layout {
+            viewport,
+            settings: LayoutSettings::default(),
+        }
+    }
+
+    pub fn card_size(&self) -> Vec2 {
+        card_size(self.viewport, &self.settings)
+    }
+
+    /// How far below its slot the last card of a tableau column sits.
+    pub fn column_depth(&self, face_down: usize, face_up: usize) -> f32 {
+        tableau_offsets(face_down, face_up, self.viewport, &self.settings)
+            .last()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn arrange(&self, game: &GameState) -> Arrangement {
+        arrange(game, self.viewport, &self.settings)
+    }
+}
+
+/// Target transforms for every card, bottom of each pile first, and every slot.
+#[derive(Clone, Default, Debug)]
+pub struct Arrangement {
+    pub cards: Vec<(Card, Transform)>,
+    pub slots: Vec<(Pile, Transform)>,
+}
+
+impl Arrangement {
+    pub fn card(&self, card: &Card) -> Option<Transform> {
+        self.cards
+            .iter()
+            .find(|(c, _)| c.matches(card))
+            .map(|(_, transform)| *transform)
+    }
+
+    pub fn slot(&self, pile: Pile) -> Transform {
+        self.slots
+            .iter()
+            .find(|(p, _)| *p == pile)
+            .map(|(_, transform)| *transform)
+            .unwrap_or_default()
+    }
+}
+
+/// How much the board is scaled from the default window, keeping it whole
+/// in whichever direction is tighter.
+fn scale(viewport: Vec2) -> f32 {
+    (viewport.x / WINDOW_WIDTH).min(viewport.y / WINDOW_HEIGHT)
+}
+
+pub fn card_size(viewport: Vec2, settings: &LayoutSettings) -> Vec2 {
+    Vec2::new(CARD_WIDTH, CARD_HEIGHT) * settings.card_scale * scale(viewport)
+}
+
+/// Seven evenly spaced columns across the middle 80% of the window, with the
+/// stock, waste and foundations above them.
+fn slot_translation(pile: Pile, viewport: Vec2, settings: &LayoutSettings) -> Vec3 {
+    let slots_width = viewport.x * 0.8;
+    let spacing = slots_width / (TABLEAU_COUNT + 1) as f32;
+    let column_x = |i: usize| -slots_width / 2.0 + spacing * (i + 1) as f32;
+    let card_height = card_size(viewport, settings).y;
+    let top_row_y = (viewport.y / 2.0) - card_height;
+    let bottom_row_y = top_row_y - card_height * 1.5;
+
+    let (x, y) = match pile {
+        Pile::Stock => (column_x(0), top_row_y),
+        Pile::Waste => (column_x(1), top_row_y),
+        Pile::Foundation(i) => (column_x(i + 3), top_row_y),
+        Pile::Tableau(i) => (column_x(i), bottom_row_y),
+    };
+    Vec3::new(x, y, -1.0)
+}
+
+/// Offset below the slot of each card in a tableau column, top first. Face-down
+/// cards take a smaller step than face-up ones, and both are squeezed in
+/// proportion once the column would run past the bottom of the window.
+fn tableau_offsets(
+    face_down: usize,
+    face_up: usize,
+    viewport: Vec2,
+    settings: &LayoutSettings,
+) -> Vec<f32> {
+    let scale = scale(viewport);
+    let steps: Vec<f32> = (1..face_down + face_up)
+        .map(|i| {
+            let above = if i <= face_down {
+                settings.face_down_fan_offset
+            } else {
+                settings.tableau_fan_offset
+            };
+            above * scale
+        })
+        .collect();
+
+    let slot = slot_translation(Pile::Tableau(0), viewport, settings);
+    let room = slot.y + viewport.y / 2.0 - card_size(viewport, settings).y / 2.0 - BOTTOM_MARGIN;
+    let wanted: f32 = steps.iter().sum();
+    let squeeze = if wanted > room {
+        room.max(0.0) / wanted
+    } else {
+        1.0
+    };
+
+    let mut offset = 0.0;
+    let mut offsets = vec![0.0; (face_down + face_up).min(1)];
+    for step in steps {
+        offset += step * squeeze;
+        offsets.push(offset);
+    }
+    offsets
+}
+
+pub fn arrange(game: &GameState, viewport: Vec2, settings: &LayoutSettings) -> Arrangement {
+    let mut piles = vec![Pile::Stock, Pile::Waste];
+    piles.extend((0..FOUNDATION_COUNT).map(Pile::Foundation));
+    piles.extend((0..TABLEAU_COUNT).map(Pile::Tableau));
+
+    let slot = |pile| slot_translation(pile, viewport, settings);
+    let scale = scale(viewport);
+    let mut arrangement = Arrangement {
+        slots: piles
+            .iter()
+            .map(|&pile| (pile, Transform::from_translation(slot(pile))))
+            .collect(),
+        ..default()
+    };
+    let mut place = |card: &Card, x: f32, y: f32, index: usize| {
+        let transform = Transform::from_xyz(x, y, index as f32);
+        arrangement.cards.push((card.clone(), transform));
+    };
+
+    let stock = slot(Pile::Stock);
+    for (i, card) in game.stock.iter().enumerate() {
+        place(card, stock.x, stock.y, i);
+    }
+
+    // The newest cards of the waste fan out to the right, one per card the
+    // draw mode turns over.
+    let waste = slot(Pile::Waste);
+    let fan_start = game.waste.len().saturating_sub(game.draw_mode.count());
+    let waste_fan = settings.waste_fan_offset * scale;
+    for (i, card) in game.waste.iter().enumerate() {
+        let x_offset = i.saturating_sub(fan_start) as f32 * waste_fan;
+        place(card, waste.x + x_offset, waste.y, i);
+    }
+
+    for (f, pile) in game.foundations.iter().enumerate() {
+        let foundation = slot(Pile::Foundation(f));
+        for (i, card) in pile.iter().enumerate() {
+            place(card, foundation.x, foundation.y, i);
+        }
+    }
+
+    for (t, column) in game.tableau.iter().enumerate() {
+        let column_slot = slot(Pile::Tableau(t));
+        let offsets = tableau_offsets(
+            column.face_down.len(),
+            column.face_up.len(),
+            viewport,
+            settings,
+        );
+        let cards = column.face_down.iter().chain(column.face_up.iter());
+        for (i, (card, offset)) in cards.zip(offsets).enumerate() {
+            place(card, column_slot.x, column_slot.y - offset, i);
+        }
+    }
+
+    arrangement
+}
+
+/// Sends every card still on the table towards where `arrange` puts it.
+/// Cards that only change stacking order are moved at once; a resize snaps
+/// everything straight into place rather than sliding it there.
+fn drive_cards(
+    mut commands: Commands,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    layout: Res<Layout>,
+    mut cards: Query<
+        (Entity, &mut Transform, &Card, Option<&MoveTo>),
+        (Without<Slot>, Without<Dragging>),
+    >,
+) {
+    let arrangement = layout.arrange(&board_state.to_game_state(&deck));
+    let snap = layout.is_changed();
+
+    for (entity, mut transform, card, moving) in cards.iter_mut() {
+        let Some(target) = arrangement.card(card).map(|t| t.translation) else {
+            continue;
+        };
+        if moving.is_some_and(|m| m.target == target) || transform.translation == target {
+            continue;
+        }
+
+        if snap || transform.translation.truncate() == target.truncate() {
+            transform.translation = target;
+            commands.entity(entity).remove::<MoveTo>();
+        } else {
+            transform.translation.z = IN_FLIGHT_Z + target.z;
+            commands.entity(entity).insert(MoveTo {
+                target,
+                speed: MOVE_SPEED,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The default window, a short wide one and a small one.
+    const VIEWPORTS: [Vec2; 3] = [
+        Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT),
+        Vec2::new(1280.0, 480.0),
+        Vec2::new(800.0, 600.0),
+    ];
+
+    /// The longest column there can be: six cards face-down under a King to Ace.
+    const FACE_DOWN: usize = 6;
+    const FACE_UP: usize = 13;
+
+    fn steps(offsets: &[f32]) -> Vec<f32> {
+        offsets.windows(2).map(|pair| pair[1] - pair[0]).collect()
+    }
+
+    #[test]
+    fn the_longest_column_stays_inside_the_window() {
+        let settings = LayoutSettings::default();
+        for viewport in VIEWPORTS {
+            let offsets = tableau_offsets(FACE_DOWN, FACE_UP, viewport, &settings);
+            assert_eq!(offsets.len(), FACE_DOWN + FACE_UP);
+
+            let slot = slot_translation(Pile::Tableau(0), viewport, &settings);
+            let card_bottom =
+                slot.y - offsets.last().unwrap() - card_size(viewport, &settings).y / 2.0;
+            assert!(
+                card_bottom >= -viewport.y / 2.0,
+                "{viewport}: bottom card reaches {card_bottom}"
+            );
+        }
+    }
+
+    #[test]
+    fn face_down_steps_are_never_larger_than_face_up_ones() {
+        let settings = LayoutSettings::default();
+        for viewport in VIEWPORTS {
+            let offsets = tableau_offsets(FACE_DOWN, FACE_UP, viewport, &settings);
+            let steps = steps(&offsets);
+            let (face_down, face_up) = steps.split_at(FACE_DOWN);
+            let largest_face_down = face_down.iter().copied().fold(0.0, f32::max);
+            let smallest_face_up = face_up.iter().copied().fold(f32::INFINITY, f32::min);
+            assert!(
+                largest_face_down <= smallest_face_up,
+                "{viewport}: face-down step {largest_face_down} > face-up step {smallest_face_up}"
+            );
+        }
+    }
+
+    #[test]
+    fn columns_that_fit_keep_the_default_offsets() {
+        let settings = LayoutSettings::default();
+        let viewport = Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+        let offsets = tableau_offsets(2, 3, viewport, &settings);
+        assert_eq!(
+            steps(&offsets),
+            [
+                FACE_DOWN_FAN_OFFSET,
+                FACE_DOWN_FAN_OFFSET,
+                TABLEAU_FAN_OFFSET,
+                TABLEAU_FAN_OFFSET,
+            ]
+        );
+        assert_eq!(
+            tableau_offsets(0, 0, viewport, &settings),
+            Vec::<f32>::new()
+        );
+        assert_eq!(tableau_offsets(0, 1, viewport, &settings), [0.0]);
+    }
+
+    #[test]
+    fn a_deal_is_arranged_on_its_slots_and_stacked_in_order() {
+        let viewport = Vec2::new(WINDOW_WIDTH, WINDOW_HEIGHT);
+        let settings = LayoutSettings::default();
+        let game = GameState::from_deck(&Deck::with_seed(4));
+        let arrangement = arrange(&game, viewport, &settings);
+        assert_eq!(arrangement.cards.len(), 52);
+
+        let stock = slot_translation(Pile::Stock, viewport, &settings);
+        for card in &game.stock {
+            let at = arrangement.card(card).unwrap().translation;
+            assert_eq!(at.truncate(), stock.truncate());
+        }
+
+        for (t, column) in game.tableau.iter().enumerate() {
+            let slot = arrangement.slot(Pile::Tableau(t)).translation;
+            assert_eq!(
+                slot,
+                slot_translation(Pile::Tableau(t), viewport, &settings)
+            );
+
+            let cards: Vec<_> = column
+                .face_down
+                .iter()
+                .chain(column.face_up.iter())
+                .map(|card| arrangement.card(card).unwrap().translation)
+                .collect();
+            assert_eq!(cards.len(), t + 1);
+            assert_eq!(cards[0].truncate(), slot.truncate());
+            for pair in cards.windows(2) {
+                assert_eq!(pair[1].x, slot.x);
+                assert!(pair[1].y < pair[0].y, "column {t} doesn't fan downwards");
+                assert!(pair[1].z > pair[0].z, "column {t} isn't stacked in order");
+            }
+        }
+    }
+}
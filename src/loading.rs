@@ -0,0 +1,206 @@
+use crate::card::CardBack;
+use crate::deck::Deck;
+use crate::hud::{HUD_FONT_SIZE, HUD_TEXT_COLOUR};
+use crate::state::AppState;
+use crate::utils::button::spawn_button;
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+/// Textures besides the card faces and backs.
+const TEXTURES: [&str; 2] = ["slot.png", "bg.png"];
+const PROGRESS_BAR_WIDTH: f32 = 400.0;
+const PROGRESS_BAR_HEIGHT: f32 = 16.0;
+const PROGRESS_TRACK_COLOUR: Color = Color::srgb(0.2, 0.2, 0.2);
+const PROGRESS_FILL_COLOUR: Color = Color::srgb(0.9, 0.9, 0.9);
+const ERROR_TEXT_COLOUR: Color = Color::srgb(1.0, 0.45, 0.4);
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetsLoading>()
+            .add_systems(
+                OnEnter(AppState::Loading),
+                (load_assets, spawn_loading_screen),
+            )
+            .add_systems(
+                Update,
+                (
+                    (check_assets_ready, update_progress_bar)
+                        .chain()
+                        .run_if(in_state(AppState::Loading)),
+                    handle_error_screen,
+                ),
+            );
+    }
+}
+
+/// Handles for every texture the game uses, kept so they stay loaded.
+#[derive(Resource, Default, Debug)]
+pub struct AssetsLoading {
+    handles: Vec<Handle<Image>>,
+    loaded: usize,
+    failed: bool,
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct ProgressFill;
+
+#[derive(Component)]
+struct QuitButton;
+
+fn load_assets(
+    asset_server: Res<AssetServer>,
+    deck: Res<Deck>,
+    mut loading: ResMut<AssetsLoading>,
+) {
+    for card in deck.get_cards() {
+        loading.handles.push(card.asset(&asset_server));
+    }
+    for back in CardBack::all() {
+        loading.handles.push(back.asset(&asset_server));
+    }
+    for path in TEXTURES {
+        loading.handles.push(asset_server.load(path));
+    }
+}
+
+fn screen_node() -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::Center,
+        row_gap: Val::Px(12.0),
+        ..default()
+    }
+}
+
+fn spawn_loading_screen(mut commands: Commands) {
+    commands
+        .spawn((screen_node(), StateScoped(AppState::Loading), LoadingScreen))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Loading..."),
+                TextFont {
+                    font_size: HUD_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(HUD_TEXT_COLOUR),
+            ));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(PROGRESS_BAR_WIDTH),
+                        height: Val::Px(PROGRESS_BAR_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(PROGRESS_TRACK_COLOUR),
+                ))
+                .with_children(|track| {
+                    track.spawn((
+                        Node {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(PROGRESS_FILL_COLOUR),
+                        ProgressFill,
+                    ));
+                });
+        });
+}
+
+fn check_assets_ready(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    mut loading: ResMut<AssetsLoading>,
+    screens: Query<Entity, With<LoadingScreen>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if loading.failed {
+        return;
+    }
+
+    let mut failed = vec![];
+    let mut loaded = 0;
+    for handle in loading.handles.iter() {
+        match server.get_load_state(handle.id()) {
+            Some(LoadState::Loaded) => loaded += 1,
+            Some(LoadState::Failed(error)) => {
+                warn!("Failed to load asset: {}", error);
+                let path = handle
+                    .path()
+                    .map_or("unknown".to_string(), |p| p.to_string());
+                failed.push(path);
+            }
+            _ => (),
+        }
+    }
+    loading.loaded = loaded;
+
+    if !failed.is_empty() {
+        loading.failed = true;
+        for entity in screens.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_error_screen(&mut commands, &failed);
+    } else if loaded == loading.handles.len() {
+        info!("Loaded all {} textures.", loaded);
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+fn update_progress_bar(
+    loading: Res<AssetsLoading>,
+    mut fills: Query<&mut Node, With<ProgressFill>>,
+) {
+    if !loading.is_changed() || loading.handles.is_empty() {
+        return;
+    }
+
+    let progress = loading.loaded as f32 / loading.handles.len() as f32;
+    for mut node in fills.iter_mut() {
+        node.width = Val::Percent(progress * 100.0);
+    }
+}
+
+/// The game can't be played with textures missing, so all that's left to do
+/// is say which ones and offer to quit.
+fn spawn_error_screen(commands: &mut Commands, failed: &[String]) {
+    commands
+        .spawn((screen_node(), StateScoped(AppState::Loading)))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Some textures couldn't be loaded:"),
+                TextFont {
+                    font_size: HUD_FONT_SIZE * 1.5,
+                    ..default()
+                },
+                TextColor(ERROR_TEXT_COLOUR),
+            ));
+            parent.spawn((
+                Text::new(failed.join("\n")),
+                TextFont {
+                    font_size: HUD_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(HUD_TEXT_COLOUR),
+            ));
+            spawn_button(parent, "Quit", QuitButton);
+        });
+}
+
+fn handle_error_screen(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Pressed) {
+        exit_writer.send(AppExit::Success);
+    }
+}
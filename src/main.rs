@@ -1,33 +1,97 @@
 #![allow(clippy::type_complexity)]
+mod autocomplete;
 mod board;
 mod card;
+mod clock;
 mod config;
 mod deck;
 mod events;
+mod focus;
+mod hint;
+mod history;
+mod hud;
+mod layout;
+mod loading;
+mod menu;
+mod moves;
+mod replay;
+mod rules;
+mod save;
+mod score;
+mod solver;
+mod state;
+mod stats;
+mod storage;
 mod types;
 mod utils;
+mod win;
 
+use autocomplete::AutoCompletePlugin;
 use board::BoardPlugin;
 use card::CardPlugin;
+use clock::ClockPlugin;
 use events::EventPlugin;
+use focus::FocusPlugin;
+use hint::HintPlugin;
+use history::HistoryPlugin;
+use hud::HudPlugin;
+use layout::LayoutPlugin;
+use loading::LoadingPlugin;
+use menu::MenuPlugin;
+use moves::MovePlugin;
+use replay::ReplayPlugin;
+use save::SavePlugin;
+use score::ScorePlugin;
+use state::AppState;
+use stats::StatsPlugin;
 use utils::UtilsPlugin;
+use win::WinPlugin;
 
 use bevy::prelude::*;
 use config::*;
 
 fn main() {
+    if let Some(deck) = solver::solve_request_from_args() {
+        solver::print_verdict(&deck);
+        return;
+    }
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Solitaire by SIV".to_string(),
                 position: WindowPosition::Centered(MonitorSelection::Primary),
                 resolution: (WINDOW_WIDTH, WINDOW_HEIGHT).into(),
-                resizable: false,
+                resizable: true,
                 ..default()
             }),
             ..default()
         }))
-        .add_plugins((EventPlugin, BoardPlugin, CardPlugin, UtilsPlugin))
+        .add_plugins((
+            EventPlugin,
+            LoadingPlugin,
+            LayoutPlugin,
+            BoardPlugin,
+            CardPlugin,
+            UtilsPlugin,
+            MovePlugin,
+            HistoryPlugin,
+            HudPlugin,
+            ClockPlugin,
+        ))
+        .add_plugins((
+            MenuPlugin,
+            FocusPlugin,
+            WinPlugin,
+            AutoCompletePlugin,
+            HintPlugin,
+            ScorePlugin,
+            StatsPlugin,
+            SavePlugin,
+            ReplayPlugin,
+        ))
+        .init_state::<AppState>()
+        .enable_state_scoped_entities::<AppState>()
         .insert_resource(ClearColor(BG_COLOUR))
         .add_systems(Startup, setup)
         .run();
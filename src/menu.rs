@@ -0,0 +1,339 @@
+use crate::board::BoardState;
+use crate::card::{CARD_HEIGHT, CARD_WIDTH, CardBack};
+use crate::events::{NewGameEvent, ResumeGameEvent};
+use crate::focus::Focus;
+use crate::hud::{GameEntry, HUD_FONT_SIZE, HUD_TEXT_COLOUR};
+use crate::replay::ReplayViewer;
+use crate::rules::DrawMode;
+use crate::save::PendingResume;
+use crate::score::{ScoreSettings, ScoringMode};
+use crate::state::AppState;
+use crate::stats::{Stats, spawn_stats_screen};
+use crate::utils::button::{CancelButton, spawn_button};
+use bevy::prelude::*;
+
+const MENU_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+const BACK_PREVIEW_SCALE: f32 = 0.15;
+const BACK_SELECTED_COLOUR: Color = Color::srgb(1.0, 0.85, 0.3);
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameOptions>()
+            .add_systems(
+                OnEnter(AppState::MainMenu),
+                spawn_main_menu.run_if(not(resource_exists::<ReplayViewer>)),
+            )
+            .add_systems(OnEnter(AppState::Paused), spawn_pause_menu)
+            .add_systems(
+                Update,
+                (
+                    (handle_main_menu, handle_options).run_if(in_state(AppState::MainMenu)),
+                    toggle_pause.run_if(in_state(AppState::Playing).or(in_state(AppState::Paused))),
+                    handle_pause_menu.run_if(in_state(AppState::Paused)),
+                ),
+            );
+    }
+}
+
+/// Choices made in the options menu that apply to the next new game.
+#[derive(Resource, Default, Debug)]
+pub struct GameOptions {
+    pub draw_mode: DrawMode,
+}
+
+#[derive(Component, Clone, Copy)]
+enum MenuButton {
+    NewGame,
+    Continue,
+    Options,
+    Statistics,
+    Quit,
+}
+
+#[derive(Component)]
+struct OptionsPanel;
+
+#[derive(Component, Clone, Copy)]
+enum OptionsButton {
+    DrawMode,
+    Scoring,
+    Bankroll,
+    CardBack(CardBack),
+    Back,
+}
+
+#[derive(Component, Clone, Copy)]
+enum PauseButton {
+    Resume,
+    MainMenu,
+    Quit,
+}
+
+fn menu_node() -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::Center,
+        row_gap: Val::Px(12.0),
+        ..default()
+    }
+}
+
+fn spawn_title(parent: &mut ChildBuilder, title: &str) {
+    parent.spawn((
+        Text::new(title),
+        TextFont {
+            font_size: HUD_FONT_SIZE * 3.0,
+            ..default()
+        },
+        TextColor(HUD_TEXT_COLOUR),
+    ));
+}
+
+/// A game can be continued if one was saved last session or one is still on
+/// the table from before going back to the menu.
+fn can_continue(pending: Option<&PendingResume>, board_state: &BoardState) -> bool {
+    pending.is_some() || board_state.play_piles.iter().any(|pile| !pile.is_empty())
+}
+
+fn spawn_main_menu(
+    mut commands: Commands,
+    pending: Option<Res<PendingResume>>,
+    board_state: Res<BoardState>,
+) {
+    let can_continue = can_continue(pending.as_deref(), &board_state);
+    commands
+        .spawn((
+            menu_node(),
+            BackgroundColor(MENU_BACKGROUND),
+            GlobalZIndex(5),
+            StateScoped(AppState::MainMenu),
+        ))
+        .with_children(|parent| {
+            spawn_title(parent, "Solitaire");
+            if can_continue {
+                spawn_button(parent, "Continue", MenuButton::Continue);
+            }
+            spawn_button(parent, "New game", MenuButton::NewGame);
+            spawn_button(parent, "Options", MenuButton::Options);
+            spawn_button(parent, "Statistics", MenuButton::Statistics);
+            spawn_button(parent, "Quit", MenuButton::Quit);
+        });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_main_menu(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    options: Res<GameOptions>,
+    score_settings: Res<ScoreSettings>,
+    stats: Res<Stats>,
+    card_back: Res<CardBack>,
+    server: Res<AssetServer>,
+    pending: Option<Res<PendingResume>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut new_game_writer: EventWriter<NewGameEvent>,
+    mut resume_writer: EventWriter<ResumeGameEvent>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::NewGame => {
+                new_game_writer.send(NewGameEvent {
+                    seed: None,
+                    draw_mode: options.draw_mode,
+                });
+            }
+            MenuButton::Continue if pending.is_some() => {
+                resume_writer.send(ResumeGameEvent);
+            }
+            MenuButton::Continue => next_state.set(AppState::Playing),
+            MenuButton::Options => spawn_options_panel(
+                &mut commands,
+                &options,
+                &score_settings,
+                &card_back,
+                &server,
+            ),
+            MenuButton::Statistics => spawn_stats_screen(&mut commands, &stats),
+            MenuButton::Quit => {
+                exit_writer.send(AppExit::Success);
+            }
+        }
+    }
+}
+
+fn spawn_options_panel(
+    commands: &mut Commands,
+    options: &GameOptions,
+    settings: &ScoreSettings,
+    card_back: &CardBack,
+    server: &Res<AssetServer>,
+) {
+    let draw = format!("Draw {}", options.draw_mode.count());
+    let scoring = match settings.mode {
+        ScoringMode::Standard => "Standard scoring",
+        ScoringMode::Vegas => "Vegas scoring",
+    };
+    let bankroll = if settings.cumulative {
+        "Vegas bankroll carries over"
+    } else {
+        "Vegas bankroll per game"
+    };
+
+    commands
+        .spawn((
+            menu_node(),
+            BackgroundColor(MENU_BACKGROUND),
+            GlobalZIndex(10),
+            StateScoped(AppState::MainMenu),
+            OptionsPanel,
+        ))
+        .with_children(|parent| {
+            spawn_title(parent, "Options");
+            spawn_button(parent, &draw, OptionsButton::DrawMode);
+            spawn_button(parent, scoring, OptionsButton::Scoring);
+            spawn_button(parent, bankroll, OptionsButton::Bankroll);
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(8.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for back in CardBack::all() {
+                        spawn_back_preview(row, back, back == *card_back, server);
+                    }
+                });
+            spawn_button(parent, "Back", (OptionsButton::Back, CancelButton));
+        });
+}
+
+fn spawn_back_preview(
+    parent: &mut ChildBuilder,
+    back: CardBack,
+    selected: bool,
+    server: &Res<AssetServer>,
+) {
+    let border = if selected {
+        BACK_SELECTED_COLOUR
+    } else {
+        Color::NONE
+    };
+    parent.spawn((
+        Button,
+        Node {
+            width: Val::Px(CARD_WIDTH * BACK_PREVIEW_SCALE),
+            height: Val::Px(CARD_HEIGHT * BACK_PREVIEW_SCALE),
+            border: UiRect::all(Val::Px(3.0)),
+            ..default()
+        },
+        BorderColor(border),
+        ImageNode::new(back.asset(server)),
+        OptionsButton::CardBack(back),
+    ));
+}
+
+/// Each choice toggles in place; the panel is rebuilt to show the new value.
+#[allow(clippy::too_many_arguments)]
+fn handle_options(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &OptionsButton), Changed<Interaction>>,
+    panels: Query<Entity, With<OptionsPanel>>,
+    mut options: ResMut<GameOptions>,
+    mut score_settings: ResMut<ScoreSettings>,
+    mut card_back: ResMut<CardBack>,
+    server: Res<AssetServer>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        for entity in panels.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        match button {
+            OptionsButton::DrawMode => options.draw_mode = options.draw_mode.toggled(),
+            OptionsButton::Scoring => score_settings.mode = score_settings.mode.toggled(),
+            OptionsButton::Bankroll => score_settings.cumulative = !score_settings.cumulative,
+            OptionsButton::CardBack(back) => {
+                *card_back = *back;
+                card_back.save();
+            }
+            OptionsButton::Back => continue,
+        }
+        spawn_options_panel(
+            &mut commands,
+            &options,
+            &score_settings,
+            &card_back,
+            &server,
+        );
+    }
+}
+
+pub fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    entry: Res<GameEntry>,
+    focus: Res<Focus>,
+    gamepads: Query<&Gamepad>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // Escape belongs to the game number prompt while it is open, and puts
+    // back cards picked up with the keyboard.
+    let escape = keys.just_pressed(KeyCode::Escape) && !entry.is_open() && !focus.is_holding();
+    let start = gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::Start));
+    if !escape && !start {
+        return;
+    }
+
+    next_state.set(match state.get() {
+        AppState::Paused => AppState::Playing,
+        _ => AppState::Paused,
+    });
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            menu_node(),
+            BackgroundColor(MENU_BACKGROUND),
+            GlobalZIndex(5),
+            StateScoped(AppState::Paused),
+        ))
+        .with_children(|parent| {
+            spawn_title(parent, "Paused");
+            spawn_button(parent, "Resume (Esc)", PauseButton::Resume);
+            spawn_button(parent, "Main menu", PauseButton::MainMenu);
+            spawn_button(parent, "Quit", PauseButton::Quit);
+        });
+}
+
+fn handle_pause_menu(
+    buttons: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PauseButton::Resume => next_state.set(AppState::Playing),
+            PauseButton::MainMenu => next_state.set(AppState::MainMenu),
+            PauseButton::Quit => {
+                exit_writer.send(AppExit::Success);
+            }
+        }
+    }
+}
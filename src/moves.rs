@@ -0,0 +1,319 @@
+use crate::board::{BoardState, Col, DeckPosition, DrawPosition, Home, Slot};
+use crate::card::{Card, CardBack, CardBundle};
+use crate::deck::Deck;
+use crate::events::{ActionAppliedEvent, MoveEvent, RedoEvent, UndoEvent};
+use crate::history::{GameAction, History, Record};
+use crate::layout::Layout;
+use crate::rules::{GameState, Move, Pile};
+use crate::state::AppState;
+use crate::utils::dragging::Draggable;
+use crate::utils::flipping::Flipping;
+use crate::utils::hovering::{HoverState, Hoverable};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+pub const MOVE_SPEED: f32 = 400.0;
+
+pub struct MovePlugin;
+
+impl Plugin for MovePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            // Moves still queued when the game is paused or left are dropped.
+            apply_moves.run_if(in_state(AppState::Playing).or(in_state(AppState::Replay))),
+        );
+    }
+}
+
+/// Everything needed to carry a move out on both the resources and the card
+/// entities, so moves, undos and redos all share one code path.
+#[derive(SystemParam)]
+pub struct Table<'w, 's> {
+    commands: Commands<'w, 's>,
+    deck: ResMut<'w, Deck>,
+    board_state: ResMut<'w, BoardState>,
+    layout: Res<'w, Layout>,
+    cards: Query<'w, 's, (Entity, &'static Card), Without<Slot>>,
+    flips: Query<'w, 's, &'static Flipping>,
+}
+
+impl Table<'_, '_> {
+    pub fn game_state(&self) -> GameState {
+        self.board_state.to_game_state(&self.deck)
+    }
+
+    /// Removes the top `count` cards of `pile`, bottom first.
+    fn take(&mut self, pile: Pile, count: usize) -> Vec<Card> {
+        let cards = match pile {
+            Pile::Stock => return vec![],
+            Pile::Waste => {
+                let mut cards: Vec<_> = (0..count).filter_map(|_| self.deck.play_drawn()).collect();
+                cards.reverse();
+                return cards;
+            }
+            Pile::Tableau(i) => &mut self.board_state.play_piles[i],
+            Pile::Foundation(i) => &mut self.board_state.home_piles[i],
+        };
+        cards.split_off(cards.len().saturating_sub(count))
+    }
+
+    fn put(&mut self, pile: Pile, cards: Vec<Card>) {
+        for card in cards.iter() {
+            self.place(card, pile);
+        }
+
+        let face_up = cards.into_iter().map(|card| Card {
+            flipped: false,
+            ..card
+        });
+        match pile {
+            Pile::Stock => (),
+            Pile::Waste => face_up.for_each(|card| self.deck.push_drawn(card)),
+            Pile::Tableau(i) => self.board_state.play_piles[i].extend(face_up),
+            Pile::Foundation(i) => self.board_state.home_piles[i].extend(face_up),
+        }
+    }
+
+    /// Re-tags the card's entity with the markers for its new pile; the
+    /// layout takes care of moving it there.
+    fn place(&mut self, card: &Card, pile: Pile) {
+        let Some((entity, _)) = self.cards.iter().find(|(_, c)| c.matches(card)) else {
+            return;
+        };
+
+        let mut entity = self.commands.entity(entity);
+        entity.remove::<(DeckPosition, DrawPosition, Col, Home)>();
+        match pile {
+            Pile::Stock => entity.remove::<Draggable>().insert(DeckPosition),
+            Pile::Waste => entity.insert((DrawPosition, Draggable)),
+            Pile::Tableau(i) => entity.insert((Col(i as u32), Draggable)),
+            Pile::Foundation(i) => entity.insert((Home(i as u32), Draggable)),
+        };
+    }
+
+    /// Animates the card's entity onto its face or its back. `Card.flipped`
+    /// only changes halfway through a flip, so one still under way decides
+    /// which side the card is headed for, and is replaced if that's wrong.
+    fn turn(&mut self, card: &Card, face_up: bool) {
+        let Some((entity, current)) = self.cards.iter().find(|(_, c)| c.matches(card)) else {
+            return;
+        };
+        let face_down = self
+            .flips
+            .get(entity)
+            .map_or(current.flipped, |flipping| flipping.face_down);
+        if face_down != face_up {
+            return;
+        }
+        self.commands.entity(entity).insert(Flipping::new(!face_up));
+    }
+
+    pub fn perform(&mut self, mv: Move) -> Record {
+        let mut record = Record {
+            mv,
+            revealed: false,
+            drawn: 0,
+        };
+        match mv {
+            Move::Draw => {
+                for _ in 0..self.deck.get_draw_mode().count() {
+                    let Some(card) = self.deck.draw() else {
+                        break;
+                    };
+                    self.place(&card, Pile::Waste);
+                    self.turn(&card, true);
+                    record.drawn += 1;
+                }
+            }
+            Move::Recycle => {
+                self.deck.reset();
+                for card in self.deck.get_cards().clone().iter() {
+                    self.place(card, Pile::Stock);
+                    self.turn(card, false);
+                }
+            }
+            Move::Transfer { from, to, count } => {
+                let cards = self.take(from, count);
+                self.put(to, cards);
+                if let Pile::Tableau(i) = from {
+                    record.revealed = self.reveal_top(i);
+                }
+            }
+        }
+        self.update_waste_draggable();
+        record
+    }
+
+    pub fn revert(&mut self, record: Record) {
+        match record.mv {
+            Move::Draw => {
+                for _ in 0..record.drawn {
+                    let Some(card) = self.deck.undraw() else {
+                        break;
+                    };
+                    self.place(&card, Pile::Stock);
+                    self.turn(&card, false);
+                }
+            }
+            Move::Recycle => {
+                self.deck.undo_reset();
+                for card in self.deck.get_drawn_cards().clone().iter() {
+                    self.place(card, Pile::Waste);
+                    self.turn(card, true);
+                }
+            }
+            Move::Transfer { from, to, count } => {
+                if let (true, Pile::Tableau(i)) = (record.revealed, from) {
+                    self.hide_top(i);
+                }
+                let cards = self.take(to, count);
+                self.put(from, cards);
+            }
+        }
+        self.update_waste_draggable();
+    }
+
+    /// Leaves only the top card of the waste draggable.
+    fn update_waste_draggable(&mut self) {
+        let waste = self.deck.get_drawn_cards().clone();
+        for (i, card) in waste.iter().enumerate() {
+            let Some((entity, _)) = self.cards.iter().find(|(_, c)| c.matches(card)) else {
+                continue;
+            };
+            let mut entity = self.commands.entity(entity);
+            if i + 1 == waste.len() {
+                entity.insert(Draggable);
+            } else {
+                entity.remove::<Draggable>();
+            }
+        }
+    }
+
+    /// Swaps in a saved game, despawning every card entity and spawning them
+    /// again where the saved piles put them.
+    pub fn restore(
+        &mut self,
+        deck: Deck,
+        board_state: BoardState,
+        server: &Res<AssetServer>,
+        back: &CardBack,
+    ) {
+        for (entity, ..) in self.cards.iter() {
+            self.commands.entity(entity).despawn();
+        }
+        *self.deck = deck;
+        *self.board_state = board_state;
+
+        let arrangement = self.layout.arrange(&self.game_state());
+        for (pile, cards) in self.piles() {
+            for (index, card) in cards.iter().enumerate() {
+                let transform = arrangement.card(card).unwrap_or_default();
+                let mut entity = self.commands.spawn((
+                    CardBundle::new(card, server, back, self.layout.card_size(), transform),
+                    Hoverable,
+                    HoverState::default(),
+                ));
+                match pile {
+                    Pile::Stock => entity.insert(DeckPosition),
+                    Pile::Waste => entity.insert(DrawPosition),
+                    Pile::Tableau(i) => entity.insert(Col(i as u32)),
+                    Pile::Foundation(i) => entity.insert(Home(i as u32)),
+                };
+                let is_top = index + 1 == cards.len();
+                let draggable = match pile {
+                    Pile::Stock => false,
+                    Pile::Waste | Pile::Foundation(_) => is_top,
+                    Pile::Tableau(_) => !card.flipped,
+                };
+                if draggable {
+                    entity.insert(Draggable);
+                }
+            }
+        }
+    }
+
+    /// Every pile's cards, bottom first, with stock and waste turned the way
+    /// they are shown.
+    fn piles(&self) -> Vec<(Pile, Vec<Card>)> {
+        let mut piles = vec![];
+        let stock = self.deck.get_cards().iter().map(|card| Card {
+            flipped: true,
+            ..card.clone()
+        });
+        piles.push((Pile::Stock, stock.collect::<Vec<_>>()));
+        let waste = self.deck.get_drawn_cards().iter().map(|card| Card {
+            flipped: false,
+            ..card.clone()
+        });
+        piles.push((Pile::Waste, waste.collect()));
+        for (i, pile) in self.board_state.play_piles.iter().enumerate() {
+            piles.push((Pile::Tableau(i), pile.clone()));
+        }
+        for (i, pile) in self.board_state.home_piles.iter().enumerate() {
+            piles.push((Pile::Foundation(i), pile.clone()));
+        }
+        piles
+    }
+
+    /// Turns over a face-down card a move left on top of column `index`,
+    /// returning whether there was one.
+    fn reveal_top(&mut self, index: usize) -> bool {
+        let Some(card) = self.board_state.play_piles[index]
+            .last_mut()
+            .filter(|card| card.flipped)
+        else {
+            return false;
+        };
+        card.flipped = false;
+        let card = card.clone();
+        self.turn(&card, true);
+        if let Some((entity, ..)) = self.cards.iter().find(|(_, c)| c.matches(&card)) {
+            self.commands.entity(entity).insert(Draggable);
+        }
+        true
+    }
+
+    fn hide_top(&mut self, index: usize) {
+        let Some(card) = self.board_state.play_piles[index].last_mut() else {
+            return;
+        };
+        card.flipped = true;
+        let card = card.clone();
+        self.turn(&card, false);
+        if let Some((entity, ..)) = self.cards.iter().find(|(_, c)| c.matches(&card)) {
+            self.commands.entity(entity).remove::<Draggable>();
+        }
+    }
+}
+
+pub fn apply_moves(
+    mut table: Table,
+    mut history: ResMut<History>,
+    mut moves: EventReader<MoveEvent>,
+    mut undos: EventReader<UndoEvent>,
+    mut redos: EventReader<RedoEvent>,
+    mut applied_writer: EventWriter<ActionAppliedEvent>,
+) {
+    for MoveEvent(mv) in moves.read() {
+        if table.game_state().is_legal(*mv) {
+            let record = table.perform(*mv);
+            history.push(record);
+            applied_writer.send(ActionAppliedEvent(GameAction::Play(*mv)));
+        }
+    }
+
+    for _ in undos.read() {
+        if let Some(record) = history.undo() {
+            table.revert(record);
+            applied_writer.send(ActionAppliedEvent(GameAction::Undo));
+        }
+    }
+
+    for _ in redos.read() {
+        if let Some(record) = history.redo() {
+            table.perform(record.mv);
+            applied_writer.send(ActionAppliedEvent(GameAction::Redo));
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use crate::clock::GameClock;
+use crate::deck::Deck;
+use crate::events::{
+    ActionAppliedEvent, GameWonEvent, MoveEvent, NewGameEvent, RecordingFinished, RedoEvent,
+    UndoEvent,
+};
+use crate::history::GameAction;
+use crate::hud::{HUD_FONT_SIZE, HUD_TEXT_COLOUR};
+use crate::moves::apply_moves;
+use crate::rules::DrawMode;
+use crate::state::AppState;
+use crate::storage;
+use crate::win::detect_win;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Bump when `Replay` changes shape; older files then won't load.
+const REPLAY_VERSION: u32 = 1;
+const REPLAY_DIR: &str = "replays";
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 8.0;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(viewer) = replay_path_from_args().and_then(|path| ReplayViewer::open(&path)) {
+            app.insert_resource(viewer);
+        }
+
+        app.init_resource::<Recording>()
+            .add_systems(
+                OnEnter(AppState::MainMenu),
+                enter_viewer.run_if(resource_exists::<ReplayViewer>),
+            )
+            .add_systems(
+                OnEnter(AppState::Replay),
+                start_viewer.run_if(resource_exists::<ReplayViewer>),
+            )
+            .add_systems(
+                Update,
+                (
+                    recording_systems(),
+                    save_replay.run_if(on_event::<RecordingFinished>),
+                    (handle_viewer_input, run_viewer, update_viewer_text)
+                        .chain()
+                        .run_if(in_state(AppState::Replay)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct TimedAction {
+    /// Game clock time when the action was taken.
+    pub at: Duration,
+    pub action: GameAction,
+}
+
+/// A deal and everything done to it, enough to play the game back exactly.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Replay {
+    version: u32,
+    seed: u64,
+    draw_mode: DrawMode,
+    actions: Vec<TimedAction>,
+}
+
+/// The replay of the game being played.
+#[derive(Resource, Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Recording(pub Replay);
+
+/// Plays a loaded replay back through the same events the player's input uses.
+#[derive(Resource, Debug)]
+pub struct ReplayViewer {
+    replay: Replay,
+    /// Index of the next action to play.
+    next: usize,
+    elapsed: Duration,
+    playing: bool,
+    speed: f32,
+}
+
+#[derive(Component)]
+struct ReplayText;
+
+impl ReplayViewer {
+    fn open(path: &Path) -> Option<Self> {
+        let Some(replay) = storage::load_from::<Replay>(path) else {
+            warn!("Couldn't read replay {}", path.display());
+            return None;
+        };
+        if replay.version != REPLAY_VERSION {
+            warn!("Replay {} is from an incompatible version", path.display());
+            return None;
+        }
+        Some(ReplayViewer {
+            replay,
+            next: 0,
+            elapsed: Duration::ZERO,
+            playing: true,
+            speed: 1.0,
+        })
+    }
+}
+
+/// `solitaire --replay <file>` opens the replay viewer instead of a new game.
+fn replay_path_from_args() -> Option<PathBuf> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--replay");
+    args.next()?;
+    args.next().map(PathBuf::from)
+}
+
+fn record_actions(
+    mut events: EventReader<ActionAppliedEvent>,
+    state: Res<State<AppState>>,
+    deck: Res<Deck>,
+    clock: Res<GameClock>,
+    mut recording: ResMut<Recording>,
+) {
+    // A replay being watched is already recorded; taking it over mustn't
+    // record its last actions a second time.
+    if *state.get() == AppState::Replay {
+        events.clear();
+        return;
+    }
+
+    for ActionAppliedEvent(action) in events.read() {
+        let replay = &mut recording.0;
+        if replay.actions.is_empty() {
+            replay.version = REPLAY_VERSION;
+            replay.seed = deck.get_seed();
+            replay.draw_mode = deck.get_draw_mode();
+        }
+        replay.actions.push(TimedAction {
+            at: clock.0.elapsed(),
+            action: *action,
+        });
+    }
+}
+
+/// Records every action the frame applied, the winning one included, before
+/// a win or a new deal closes the recording. Recording isn't limited to
+/// `Playing`, as a win moves the game on to `Won` straight after.
+fn recording_systems() -> impl IntoSystemConfigs<()> {
+    (
+        record_actions.after(apply_moves),
+        finish_recording
+            .after(detect_win)
+            .run_if(on_event::<GameWonEvent>.or(on_event::<NewGameEvent>)),
+    )
+        .chain()
+}
+
+/// Hands the finished game's replay on to be saved and starts recording
+/// afresh. A game still in progress at exit isn't finished: the autosave
+/// carries its recording so the replay is saved whole once the resumed game is
+/// won or abandoned.
+fn finish_recording(
+    mut recording: ResMut<Recording>,
+    mut finished_writer: EventWriter<RecordingFinished>,
+) {
+    let replay = std::mem::take(&mut recording.0);
+    if !replay.actions.is_empty() {
+        finished_writer.send(RecordingFinished(replay));
+    }
+}
+
+fn save_replay(mut events: EventReader<RecordingFinished>) {
+    for RecordingFinished(replay) in events.read() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let file = format!("{}/game-{}-{}.ron", REPLAY_DIR, replay.seed, stamp);
+        storage::save(&file, replay);
+        if let Some(path) = storage::data_path(&file) {
+            info!("Saved replay to {}", path.display());
+        }
+    }
+}
+
+/// Goes straight on to the replay once the cards have loaded.
+fn enter_viewer(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Replay);
+}
+
+fn start_viewer(
+    mut commands: Commands,
+    viewer: Res<ReplayViewer>,
+    mut new_game_writer: EventWriter<NewGameEvent>,
+) {
+    new_game_writer.send(NewGameEvent {
+        seed: Some(viewer.replay.seed),
+        draw_mode: viewer.replay.draw_mode,
+    });
+
+    commands.spawn((
+        Text::default(),
+        TextFont {
+            font_size: HUD_FONT_SIZE,
+            ..default()
+        },
+        TextColor(HUD_TEXT_COLOUR),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        StateScoped(AppState::Replay),
+        ReplayText,
+    ));
+}
+
+fn send_action(
+    action: GameAction,
+    move_writer: &mut EventWriter<MoveEvent>,
+    undo_writer: &mut EventWriter<UndoEvent>,
+    redo_writer: &mut EventWriter<RedoEvent>,
+) {
+    match action {
+        GameAction::Play(mv) => {
+            move_writer.send(MoveEvent(mv));
+        }
+        GameAction::Undo => {
+            undo_writer.send(UndoEvent);
+        }
+        GameAction::Redo => {
+            redo_writer.send(RedoEvent);
+        }
+    }
+}
+
+/// Space pauses, the arrow keys step and change speed, and Escape takes over
+/// the game from the current position.
+#[allow(clippy::too_many_arguments)]
+fn handle_viewer_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut viewer: ResMut<ReplayViewer>,
+    mut recording: ResMut<Recording>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut move_writer: EventWriter<MoveEvent>,
+    mut undo_writer: EventWriter<UndoEvent>,
+    mut redo_writer: EventWriter<RedoEvent>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        viewer.playing = !viewer.playing;
+    } else if keys.just_pressed(KeyCode::ArrowUp) {
+        viewer.speed = (viewer.speed * 2.0).min(MAX_SPEED);
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        viewer.speed = (viewer.speed / 2.0).max(MIN_SPEED);
+    } else if keys.just_pressed(KeyCode::ArrowRight) {
+        viewer.playing = false;
+        if let Some(timed) = viewer.replay.actions.get(viewer.next).copied() {
+            send_action(
+                timed.action,
+                &mut move_writer,
+                &mut undo_writer,
+                &mut redo_writer,
+            );
+            viewer.next += 1;
+            viewer.elapsed = timed.at;
+        }
+    } else if keys.just_pressed(KeyCode::ArrowLeft) {
+        viewer.playing = false;
+        if let Some(previous) = viewer.next.checked_sub(1) {
+            let timed = viewer.replay.actions[previous];
+            send_action(
+                timed.action.inverse(),
+                &mut move_writer,
+                &mut undo_writer,
+                &mut redo_writer,
+            );
+            viewer.next = previous;
+            viewer.elapsed = previous
+                .checked_sub(1)
+                .map_or(Duration::ZERO, |i| viewer.replay.actions[i].at);
+        }
+    } else if keys.just_pressed(KeyCode::Escape) {
+        let mut replay = viewer.replay.clone();
+        replay.actions.truncate(viewer.next);
+        recording.0 = replay;
+        commands.remove_resource::<ReplayViewer>();
+        next_state.set(AppState::Playing);
+    }
+}
+
+/// Plays at most one action a frame, as undos and redos sent alongside a move
+/// wouldn't be applied in the order they were sent.
+fn run_viewer(
+    time: Res<Time>,
+    viewer: Option<ResMut<ReplayViewer>>,
+    mut move_writer: EventWriter<MoveEvent>,
+    mut undo_writer: EventWriter<UndoEvent>,
+    mut redo_writer: EventWriter<RedoEvent>,
+) {
+    let Some(mut viewer) = viewer.filter(|viewer| viewer.playing) else {
+        return;
+    };
+
+    let step = time.delta().mul_f32(viewer.speed);
+    viewer.elapsed += step;
+    let Some(timed) = viewer.replay.actions.get(viewer.next).copied() else {
+        viewer.playing = false;
+        return;
+    };
+    if timed.at <= viewer.elapsed {
+        send_action(
+            timed.action,
+            &mut move_writer,
+            &mut undo_writer,
+            &mut redo_writer,
+        );
+        viewer.next += 1;
+    }
+}
+
+fn update_viewer_text(
+    viewer: Option<Res<ReplayViewer>>,
+    mut query: Query<&mut Text, With<ReplayText>>,
+) {
+    let Some(viewer) = viewer.filter(|viewer| viewer.is_changed()) else {
+        return;
+    };
+
+    let status = if viewer.playing { "" } else { "  (paused)" };
+    for mut text in query.iter_mut() {
+        text.0 = format!(
+            "Replay {}/{}  x{}{}  -  Space pause, Left/Right step, Up/Down speed, Esc take over",
+            viewer.next,
+            viewer.replay.actions.len(),
+            viewer.speed,
+            status
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::BoardState;
+    use crate::events::EventPlugin;
+    use crate::history::History;
+    use crate::layout::Layout;
+    use crate::rules::GameState;
+    use crate::solver::{Budget, Verdict, solve_deck};
+    use bevy::state::app::StatesPlugin;
+
+    /// Deals `deck` onto the board the way `setup_cards` does.
+    fn deal(deck: &mut Deck) -> BoardState {
+        let mut board_state = BoardState::default();
+        let play_piles = &mut board_state.play_piles;
+        for i in 0..play_piles.len() {
+            for (j, pile) in play_piles.iter_mut().enumerate().skip(i) {
+                let mut card = deck.play().unwrap();
+                card.flipped = j != i;
+                pile.push(card);
+            }
+        }
+        board_state
+    }
+
+    #[test]
+    fn a_recorded_win_replays_to_the_won_position() {
+        let mut deck = Deck::with_seed(4);
+        let Verdict::Winnable(moves) = solve_deck(&deck, Budget::default()) else {
+            panic!("game #4 should be winnable");
+        };
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, EventPlugin))
+            .insert_state(AppState::Playing)
+            .insert_resource(deal(&mut deck))
+            .insert_resource(deck)
+            .init_resource::<Layout>()
+            .init_resource::<History>()
+            .init_resource::<GameClock>()
+            .init_resource::<Recording>()
+            .add_systems(
+                Update,
+                ((apply_moves, detect_win).chain(), recording_systems()),
+            );
+
+        // One move a frame, then a spare frame for a win spotted a frame late.
+        let mut finished = vec![];
+        for mv in moves.into_iter().map(Some).chain([None]) {
+            if let Some(mv) = mv {
+                app.world_mut().send_event(MoveEvent(mv));
+            }
+            app.update();
+            let mut events = app.world_mut().resource_mut::<Events<RecordingFinished>>();
+            finished.extend(events.drain().map(|RecordingFinished(replay)| replay));
+        }
+
+        let [replay] = finished.as_slice() else {
+            panic!("expected one finished recording, got {}", finished.len());
+        };
+        let mut game = GameState::from_deck(&Deck::with_seed(replay.seed));
+        for timed in replay.actions.iter() {
+            let GameAction::Play(mv) = timed.action else {
+                panic!("only moves were played");
+            };
+            game.apply(mv).unwrap();
+        }
+        assert!(game.is_won());
+        assert!(app.world().resource::<Recording>().0.actions.is_empty());
+    }
+}
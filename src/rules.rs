@@ -0,0 +1,511 @@
+//! Headless Klondike rules.
+//!
+//! Nothing in here touches the ECS, so the whole game can be played, searched
+//! and checked without a running `App`. The Bevy systems keep `Deck` and
+//! `BoardState` as their source of truth and build a `GameState` from them
+//! whenever they need to ask the rules a question.
+
+use crate::card::{Card, Rank};
+use crate::deck::Deck;
+use serde::{Deserialize, Serialize};
+
+pub const TABLEAU_COUNT: usize = 7;
+pub const FOUNDATION_COUNT: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub enum DrawMode {
+    #[default]
+    One,
+    Three,
+}
+
+impl DrawMode {
+    pub fn count(self) -> usize {
+        match self {
+            DrawMode::One => 1,
+            DrawMode::Three => 3,
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            DrawMode::One => DrawMode::Three,
+            DrawMode::Three => DrawMode::One,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Pile {
+    Stock,
+    Waste,
+    Tableau(usize),
+    Foundation(usize),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Move {
+    /// Turn the top card, or three in `DrawMode::Three`, of the stock over onto the waste.
+    Draw,
+    /// Turn the whole waste back over into the stock.
+    Recycle,
+    /// Move the top `count` cards of `from` onto `to`.
+    Transfer { from: Pile, to: Pile, count: usize },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IllegalMove(pub Move);
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Column {
+    pub face_down: Vec<Card>,
+    pub face_up: Vec<Card>,
+}
+
+impl Column {
+    pub fn is_empty(&self) -> bool {
+        self.face_down.is_empty() && self.face_up.is_empty()
+    }
+}
+
+/// Every pile's top card is its last element.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GameState {
+    pub stock: Vec<Card>,
+    pub waste: Vec<Card>,
+    pub tableau: Vec<Column>,
+    pub foundations: Vec<Vec<Card>>,
+    pub draw_mode: DrawMode,
+}
+
+impl GameState {
+    /// Deals `cards` exactly the way `setup_cards` does, popping from the end:
+    /// row by row, left to right, with the first card of each row face-up.
+    pub fn deal(mut cards: Vec<Card>, draw_mode: DrawMode) -> Self {
+        let mut tableau: Vec<Column> = (0..TABLEAU_COUNT).map(|_| Column::default()).collect();
+        for i in 0..TABLEAU_COUNT {
+            for (j, column) in tableau.iter_mut().enumerate().skip(i) {
+                let Some(mut card) = cards.pop() else {
+                    break;
+                };
+                card.flipped = j != i;
+                if card.flipped {
+                    column.face_down.push(card);
+                } else {
+                    column.face_up.push(card);
+                }
+            }
+        }
+        for card in cards.iter_mut() {
+            card.flipped = true;
+        }
+
+        GameState {
+            stock: cards,
+            waste: vec![],
+            tableau,
+            foundations: (0..FOUNDATION_COUNT).map(|_| Vec::new()).collect(),
+            draw_mode,
+        }
+    }
+
+    pub fn from_deck(deck: &Deck) -> Self {
+        GameState::deal(deck.get_cards().clone(), deck.get_draw_mode())
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.foundations.iter().all(|pile| pile.len() == 13)
+    }
+
+    /// Nothing left in the stock or waste and no face-down tableau cards, so
+    /// the game can be finished by moving cards home one at a time.
+    pub fn is_fully_revealed(&self) -> bool {
+        self.stock.is_empty()
+            && self.waste.is_empty()
+            && self
+                .tableau
+                .iter()
+                .all(|column| column.face_down.is_empty())
+    }
+
+    fn foundation_moves(&self) -> impl Iterator<Item = Move> {
+        self.legal_moves().into_iter().filter(|mv| {
+            matches!(
+                mv,
+                Move::Transfer {
+                    to: Pile::Foundation(_),
+                    ..
+                }
+            )
+        })
+    }
+
+    /// The legal move that sends the lowest-ranked available card home.
+    pub fn next_foundation_move(&self) -> Option<Move> {
+        self.foundation_moves().min_by_key(|mv| match mv {
+            Move::Transfer { from, .. } => self
+                .top_cards(*from, 1)
+                .map_or(u8::MAX, |cards| cards[0].rank.value()),
+            _ => u8::MAX,
+        })
+    }
+
+    /// The legal move that sends the top card of `from` home, if there is one.
+    pub fn home_move(&self, from: Pile) -> Option<Move> {
+        self.foundation_moves().find(|mv| match mv {
+            Move::Transfer { from: source, .. } => *source == from,
+            _ => false,
+        })
+    }
+
+    /// A card can go home without ever being needed on the tableau again once
+    /// both opposite-coloured suits are no more than one rank behind it.
+    pub fn is_safe_home(&self, card: &Card) -> bool {
+        let value = card.rank.value();
+        if value <= 2 {
+            return true;
+        }
+        let opposite: Vec<u8> = self
+            .foundations
+            .iter()
+            .filter_map(|pile| pile.last())
+            .filter(|top| top.suit.is_red() != card.suit.is_red())
+            .map(|top| top.rank.value())
+            .collect();
+        opposite.len() == 2 && opposite.iter().all(|&height| height + 1 >= value)
+    }
+
+    /// A foundation move for a card that `is_safe_home`, if there is one.
+    pub fn next_safe_foundation_move(&self) -> Option<Move> {
+        self.foundation_moves().find(|mv| match mv {
+            Move::Transfer { from, .. } => self
+                .top_cards(*from, 1)
+                .is_some_and(|cards| self.is_safe_home(&cards[0])),
+            _ => false,
+        })
+    }
+
+    /// The cards a `Transfer` of `count` from `pile` would pick up, bottom first.
+    pub fn top_cards(&self, pile: Pile, count: usize) -> Option<&[Card]> {
+        let cards: &[Card] = match pile {
+            Pile::Stock => return None,
+            Pile::Waste => &self.waste,
+            Pile::Tableau(i) => &self.tableau.get(i)?.face_up,
+            Pile::Foundation(i) => self.foundations.get(i)?,
+        };
+        if count == 0 || count > cards.len() {
+            return None;
+        }
+        Some(&cards[cards.len() - count..])
+    }
+
+    pub fn is_legal(&self, mv: Move) -> bool {
+        match mv {
+            Move::Draw => !self.stock.is_empty(),
+            Move::Recycle => self.stock.is_empty() && !self.waste.is_empty(),
+            Move::Transfer { from, to, count } => {
+                if from == to {
+                    return false;
+                }
+                let Some(cards) = self.top_cards(from, count) else {
+                    return false;
+                };
+                let head = &cards[0];
+                match to {
+                    Pile::Stock | Pile::Waste => false,
+                    Pile::Tableau(i) => match self.tableau.get(i).map(|c| c.face_up.last()) {
+                        None => false,
+                        Some(None) => self.tableau[i].is_empty() && head.rank == Rank::King,
+                        Some(Some(top)) => head.can_stack_on(top),
+                    },
+                    Pile::Foundation(i) => {
+                        count == 1
+                            && !matches!(from, Pile::Foundation(_))
+                            && self
+                                .foundations
+                                .get(i)
+                                .is_some_and(|pile| head.can_move_home(pile.last()))
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut sources = vec![(Pile::Waste, 1.min(self.waste.len()))];
+        for (i, column) in self.tableau.iter().enumerate() {
+            sources.push((Pile::Tableau(i), column.face_up.len()));
+        }
+        for (i, pile) in self.foundations.iter().enumerate() {
+            sources.push((Pile::Foundation(i), 1.min(pile.len())));
+        }
+
+        let targets: Vec<Pile> = (0..FOUNDATION_COUNT)
+            .map(Pile::Foundation)
+            .chain((0..TABLEAU_COUNT).map(Pile::Tableau))
+            .collect();
+
+        let mut moves = vec![];
+        for (from, max_count) in sources {
+            for count in 1..=max_count {
+                for &to in targets.iter() {
+                    let mv = Move::Transfer { from, to, count };
+                    if self.is_legal(mv) {
+                        moves.push(mv);
+                    }
+                }
+            }
+        }
+        for mv in [Move::Draw, Move::Recycle] {
+            if self.is_legal(mv) {
+                moves.push(mv);
+            }
+        }
+        moves
+    }
+
+    /// Applies `mv`, returning the tableau card it turned face-up, if any.
+    pub fn apply(&mut self, mv: Move) -> Result<Option<Card>, IllegalMove> {
+        if !self.is_legal(mv) {
+            return Err(IllegalMove(mv));
+        }
+
+        match mv {
+            Move::Draw => {
+                for _ in 0..self.draw_mode.count() {
+                    let Some(mut card) = self.stock.pop() else {
+                        break;
+                    };
+                    card.flipped = false;
+                    self.waste.push(card);
+                }
+                Ok(None)
+            }
+            Move::Recycle => {
+                self.stock = self.waste.drain(..).rev().collect();
+                for card in self.stock.iter_mut() {
+                    card.flipped = true;
+                }
+                Ok(None)
+            }
+            Move::Transfer { from, to, count } => {
+                let source = match from {
+                    Pile::Stock => unreachable!(),
+                    Pile::Waste => &mut self.waste,
+                    Pile::Tableau(i) => &mut self.tableau[i].face_up,
+                    Pile::Foundation(i) => &mut self.foundations[i],
+                };
+                let cards = source.split_off(source.len() - count);
+                match to {
+                    Pile::Tableau(i) => self.tableau[i].face_up.extend(cards),
+                    Pile::Foundation(i) => self.foundations[i].extend(cards),
+                    Pile::Stock | Pile::Waste => unreachable!(),
+                }
+                Ok(match from {
+                    Pile::Tableau(i) => self.reveal(i),
+                    _ => None,
+                })
+            }
+        }
+    }
+
+    fn reveal(&mut self, index: usize) -> Option<Card> {
+        let column = &mut self.tableau[index];
+        if !column.face_up.is_empty() {
+            return None;
+        }
+        let mut card = column.face_down.pop()?;
+        card.flipped = false;
+        column.face_up.push(card.clone());
+        Some(card)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Suit;
+    use strum::IntoEnumIterator;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card {
+            rank,
+            suit,
+            flipped: false,
+        }
+    }
+
+    fn face_down(rank: Rank, suit: Suit) -> Card {
+        Card {
+            flipped: true,
+            ..card(rank, suit)
+        }
+    }
+
+    fn empty(draw_mode: DrawMode) -> GameState {
+        GameState {
+            stock: vec![],
+            waste: vec![],
+            tableau: (0..TABLEAU_COUNT).map(|_| Column::default()).collect(),
+            foundations: (0..FOUNDATION_COUNT).map(|_| Vec::new()).collect(),
+            draw_mode,
+        }
+    }
+
+    fn transfer(from: Pile, to: Pile, count: usize) -> Move {
+        Move::Transfer { from, to, count }
+    }
+
+    #[test]
+    fn only_a_king_goes_onto_an_empty_column() {
+        let mut game = empty(DrawMode::One);
+        game.waste.push(card(Rank::Queen, Suit::Hearts));
+        assert!(!game.is_legal(transfer(Pile::Waste, Pile::Tableau(0), 1)));
+
+        game.waste.push(card(Rank::King, Suit::Hearts));
+        assert!(game.is_legal(transfer(Pile::Waste, Pile::Tableau(0), 1)));
+
+        // A column with only face-down cards left is not empty.
+        game.tableau[0]
+            .face_down
+            .push(face_down(Rank::Two, Suit::Clubs));
+        assert!(!game.is_legal(transfer(Pile::Waste, Pile::Tableau(0), 1)));
+    }
+
+    #[test]
+    fn tableau_stacks_alternate_colour() {
+        let mut game = empty(DrawMode::One);
+        game.tableau[0]
+            .face_up
+            .push(card(Rank::Eight, Suit::Spades));
+        game.tableau[1].face_up.push(card(Rank::Seven, Suit::Clubs));
+        game.tableau[2]
+            .face_up
+            .push(card(Rank::Seven, Suit::Hearts));
+        game.tableau[3]
+            .face_up
+            .push(card(Rank::Six, Suit::Diamonds));
+
+        assert!(!game.is_legal(transfer(Pile::Tableau(1), Pile::Tableau(0), 1)));
+        assert!(game.is_legal(transfer(Pile::Tableau(2), Pile::Tableau(0), 1)));
+        assert!(!game.is_legal(transfer(Pile::Tableau(3), Pile::Tableau(0), 1)));
+    }
+
+    #[test]
+    fn foundations_take_the_next_rank_of_the_same_suit() {
+        let mut game = empty(DrawMode::One);
+        game.foundations[0].push(card(Rank::Ace, Suit::Hearts));
+        game.waste.push(card(Rank::Two, Suit::Diamonds));
+        assert!(!game.is_legal(transfer(Pile::Waste, Pile::Foundation(0), 1)));
+
+        game.waste.push(card(Rank::Three, Suit::Hearts));
+        assert!(!game.is_legal(transfer(Pile::Waste, Pile::Foundation(0), 1)));
+
+        game.waste.push(card(Rank::Two, Suit::Hearts));
+        assert!(game.is_legal(transfer(Pile::Waste, Pile::Foundation(0), 1)));
+        assert!(!game.is_legal(transfer(Pile::Waste, Pile::Foundation(1), 1)));
+
+        game.waste.push(card(Rank::Ace, Suit::Spades));
+        assert!(game.is_legal(transfer(Pile::Waste, Pile::Foundation(1), 1)));
+    }
+
+    #[test]
+    fn cards_never_move_between_foundations() {
+        let mut game = empty(DrawMode::One);
+        game.foundations[0].push(card(Rank::Ace, Suit::Clubs));
+        assert!(!game.is_legal(transfer(Pile::Foundation(0), Pile::Foundation(1), 1)));
+    }
+
+    #[test]
+    fn a_face_down_card_cannot_head_a_move() {
+        let mut game = empty(DrawMode::One);
+        game.tableau[0]
+            .face_down
+            .push(face_down(Rank::King, Suit::Clubs));
+        game.tableau[0]
+            .face_up
+            .push(card(Rank::Queen, Suit::Hearts));
+        game.tableau[1].face_up.push(card(Rank::King, Suit::Spades));
+
+        assert!(game.is_legal(transfer(Pile::Tableau(0), Pile::Tableau(1), 1)));
+        assert!(!game.is_legal(transfer(Pile::Tableau(0), Pile::Tableau(2), 2)));
+    }
+
+    #[test]
+    fn apply_reveals_the_newly_exposed_card() {
+        let mut game = empty(DrawMode::One);
+        game.tableau[0]
+            .face_down
+            .push(face_down(Rank::Four, Suit::Clubs));
+        game.tableau[0]
+            .face_up
+            .push(card(Rank::Queen, Suit::Hearts));
+        game.tableau[1].face_up.push(card(Rank::King, Suit::Spades));
+
+        let revealed = game.apply(transfer(Pile::Tableau(0), Pile::Tableau(1), 1));
+        assert_eq!(revealed, Ok(Some(card(Rank::Four, Suit::Clubs))));
+        assert!(game.tableau[0].face_down.is_empty());
+        assert_eq!(game.tableau[0].face_up, vec![card(Rank::Four, Suit::Clubs)]);
+
+        // Moves that leave face-up cards behind turn nothing over.
+        game.tableau[1]
+            .face_down
+            .push(face_down(Rank::Five, Suit::Clubs));
+        game.tableau[2].face_up.push(card(Rank::King, Suit::Clubs));
+        let revealed = game.apply(transfer(Pile::Tableau(1), Pile::Tableau(2), 1));
+        assert_eq!(revealed, Ok(None));
+    }
+
+    #[test]
+    fn recycle_restores_the_stock_order() {
+        let mut game = empty(DrawMode::One);
+        game.stock = vec![
+            face_down(Rank::Ace, Suit::Clubs),
+            face_down(Rank::Two, Suit::Clubs),
+            face_down(Rank::Three, Suit::Clubs),
+        ];
+        let stock = game.stock.clone();
+
+        while game.is_legal(Move::Draw) {
+            game.apply(Move::Draw).unwrap();
+        }
+        assert!(!game.is_legal(Move::Draw));
+        assert_eq!(game.waste.last(), Some(&card(Rank::Ace, Suit::Clubs)));
+
+        game.apply(Move::Recycle).unwrap();
+        assert_eq!(game.stock, stock);
+        assert!(game.waste.is_empty());
+        assert!(!game.is_legal(Move::Recycle));
+    }
+
+    #[test]
+    fn draw_three_takes_what_is_left_of_a_short_stock() {
+        let mut game = empty(DrawMode::Three);
+        game.stock = vec![
+            face_down(Rank::Ace, Suit::Hearts),
+            face_down(Rank::Two, Suit::Hearts),
+        ];
+
+        assert_eq!(game.apply(Move::Draw), Ok(None));
+        assert!(game.stock.is_empty());
+        assert_eq!(
+            game.waste,
+            vec![card(Rank::Two, Suit::Hearts), card(Rank::Ace, Suit::Hearts)]
+        );
+        assert!(!game.is_legal(Move::Draw));
+    }
+
+    #[test]
+    fn won_once_every_foundation_is_full() {
+        let mut game = empty(DrawMode::One);
+        assert!(!game.is_won());
+
+        for (pile, suit) in game.foundations.iter_mut().zip(Suit::iter()) {
+            pile.extend(Rank::iter().map(|rank| card(rank, suit.clone())));
+        }
+        assert!(game.is_won());
+
+        game.foundations[3].pop();
+        assert!(!game.is_won());
+    }
+}
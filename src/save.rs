@@ -0,0 +1,149 @@
+use crate::board::BoardState;
+use crate::card::CardBack;
+use crate::clock::GameClock;
+use crate::deck::Deck;
+use crate::events::{GameWonEvent, NewGameEvent, ResumeGameEvent, ScoreChanged};
+use crate::history::History;
+use crate::moves::Table;
+use crate::replay::{Recording, ReplayViewer};
+use crate::score::Score;
+use crate::state::AppState;
+use crate::stats::GameTally;
+use crate::storage;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SAVE_FILE: &str = "autosave.ron";
+/// Bump when `SavedGame` changes shape; older saves are then ignored.
+const SAVE_VERSION: u32 = 2;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Startup,
+            load_saved_game.run_if(not(resource_exists::<ReplayViewer>)),
+        )
+        .add_systems(
+            Update,
+            (
+                resume_saved_game
+                    .run_if(on_event::<ResumeGameEvent>)
+                    .run_if(resource_exists::<PendingResume>),
+                drop_saved_game.run_if(on_event::<NewGameEvent>),
+                autosave
+                    .run_if(in_state(AppState::Playing))
+                    .run_if(resource_changed::<History>)
+                    .run_if(not(resource_exists::<PendingResume>)),
+                discard_save.run_if(on_event::<GameWonEvent>),
+            )
+                .chain(),
+        )
+        .add_systems(
+            Last,
+            autosave
+                .run_if(in_state(AppState::Playing).or(in_state(AppState::Paused)))
+                .run_if(on_event::<AppExit>)
+                .run_if(not(resource_exists::<PendingResume>)),
+        );
+    }
+}
+
+/// Everything needed to pick a game back up where it was left.
+#[derive(Serialize, Deserialize, Debug)]
+struct SavedGame {
+    version: u32,
+    deck: Deck,
+    board_state: BoardState,
+    history: History,
+    elapsed: Duration,
+    score: Score,
+    recording: Recording,
+}
+
+/// A save found at startup that the player can pick up from the main menu.
+#[derive(Resource)]
+pub struct PendingResume(SavedGame);
+
+fn autosave(
+    deck: Res<Deck>,
+    board_state: Res<BoardState>,
+    history: Res<History>,
+    clock: Res<GameClock>,
+    score: Res<Score>,
+    recording: Res<Recording>,
+) {
+    // A deal nobody has touched isn't worth offering back.
+    if history.len() == 0 {
+        storage::remove(SAVE_FILE);
+        return;
+    }
+
+    storage::save(
+        SAVE_FILE,
+        &SavedGame {
+            version: SAVE_VERSION,
+            deck: deck.clone(),
+            board_state: board_state.clone(),
+            history: history.clone(),
+            elapsed: clock.0.elapsed(),
+            score: score.clone(),
+            recording: recording.clone(),
+        },
+    );
+}
+
+fn discard_save() {
+    storage::remove(SAVE_FILE);
+}
+
+fn load_saved_game(mut commands: Commands) {
+    if let Some(saved) =
+        storage::load::<SavedGame>(SAVE_FILE).filter(|saved| saved.version == SAVE_VERSION)
+    {
+        commands.insert_resource(PendingResume(saved));
+    }
+}
+
+/// Starting a new game instead means the old save is about to be replaced.
+fn drop_saved_game(mut commands: Commands) {
+    commands.remove_resource::<PendingResume>();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn resume_saved_game(
+    mut commands: Commands,
+    pending: Res<PendingResume>,
+    server: Res<AssetServer>,
+    back: Res<CardBack>,
+    mut table: Table,
+    mut history: ResMut<History>,
+    mut clock: ResMut<GameClock>,
+    mut score: ResMut<Score>,
+    mut tally: ResMut<GameTally>,
+    mut recording: ResMut<Recording>,
+    mut score_writer: EventWriter<ScoreChanged>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    commands.remove_resource::<PendingResume>();
+    let saved = &pending.0;
+    table.restore(
+        saved.deck.clone(),
+        saved.board_state.clone(),
+        &server,
+        &back,
+    );
+    *history = saved.history.clone();
+    clock.0.set_elapsed(saved.elapsed);
+    *score = saved.score.clone();
+    *recording = saved.recording.clone();
+    // Already counted as played when it was first started.
+    tally.in_progress = Some(saved.deck.get_draw_mode());
+    score_writer.send(ScoreChanged {
+        points: score.get_points(),
+        mode: score.get_mode(),
+    });
+    next_state.set(AppState::Playing);
+}
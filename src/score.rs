@@ -0,0 +1,290 @@
+use crate::clock::GameClock;
+use crate::deck::Deck;
+use crate::events::{GameWonEvent, NewGameEvent, ScoreChanged};
+use crate::history::{History, Record};
+use crate::rules::{DrawMode, Move, Pile};
+use crate::win::detect_win;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const VEGAS_BUY_IN: i32 = -52;
+const TIME_PENALTY_INTERVAL: u64 = 10;
+const TIME_PENALTY: i32 = -2;
+/// Standard wins faster than this get no time bonus.
+const TIME_BONUS_MIN_SECS: u64 = 30;
+const TIME_BONUS_NUMERATOR: u64 = 700_000;
+/// Draw-three games may go through the stock this many times for free.
+const FREE_RECYCLES_DRAW_THREE: usize = 3;
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScoreSettings>()
+            .init_resource::<Score>()
+            .add_systems(
+                Update,
+                (reset_score, award_time_bonus, update_score)
+                    .chain()
+                    .after(detect_win),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum ScoringMode {
+    #[default]
+    Standard,
+    Vegas,
+}
+
+impl ScoringMode {
+    pub fn toggled(self) -> Self {
+        match self {
+            ScoringMode::Standard => ScoringMode::Vegas,
+            ScoringMode::Vegas => ScoringMode::Standard,
+        }
+    }
+}
+
+/// Scoring to use from the next deal onwards.
+#[derive(Resource, Default, Debug)]
+pub struct ScoreSettings {
+    pub mode: ScoringMode,
+    /// Carry the Vegas bankroll over from one deal to the next.
+    pub cumulative: bool,
+}
+
+/// The current deal's score. Everything but the time bonus and penalty is
+/// worked out again from `History`, so undoing a move also undoes its points.
+#[derive(Resource, Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Score {
+    mode: ScoringMode,
+    /// Points before any moves: zero, or the Vegas buy-in and bankroll.
+    base: i32,
+    bonus: i32,
+    points: i32,
+}
+
+impl Score {
+    pub fn get_mode(&self) -> ScoringMode {
+        self.mode
+    }
+
+    pub fn get_points(&self) -> i32 {
+        self.points
+    }
+}
+
+/// Points for the moves in `records`, played in order.
+pub fn move_points(mode: ScoringMode, draw_mode: DrawMode, records: &[Record]) -> i32 {
+    let mut recycles = 0;
+    records
+        .iter()
+        .map(|record| match mode {
+            ScoringMode::Standard => {
+                let reveal = if record.revealed { 5 } else { 0 };
+                let points = match record.mv {
+                    Move::Transfer {
+                        from: Pile::Waste,
+                        to: Pile::Tableau(_),
+                        ..
+                    } => 5,
+                    Move::Transfer {
+                        from: Pile::Foundation(_),
+                        ..
+                    } => -15,
+                    Move::Transfer {
+                        to: Pile::Foundation(_),
+                        ..
+                    } => 10,
+                    Move::Recycle => {
+                        recycles += 1;
+                        match draw_mode {
+                            DrawMode::One => -100,
+                            DrawMode::Three if recycles > FREE_RECYCLES_DRAW_THREE => -20,
+                            DrawMode::Three => 0,
+                        }
+                    }
+                    _ => 0,
+                };
+                points + reveal
+            }
+            ScoringMode::Vegas => match record.mv {
+                Move::Transfer {
+                    from: Pile::Foundation(_),
+                    ..
+                } => -5,
+                Move::Transfer {
+                    to: Pile::Foundation(_),
+                    ..
+                } => 5,
+                _ => 0,
+            },
+        })
+        .sum()
+}
+
+fn reset_score(
+    mut events: EventReader<NewGameEvent>,
+    settings: Res<ScoreSettings>,
+    mut score: ResMut<Score>,
+    mut score_writer: EventWriter<ScoreChanged>,
+) {
+    if events.read().last().is_none() {
+        return;
+    }
+
+    let bankroll = match (settings.mode, score.mode) {
+        (ScoringMode::Vegas, ScoringMode::Vegas) if settings.cumulative => score.points,
+        _ => 0,
+    };
+    let base = match settings.mode {
+        ScoringMode::Standard => 0,
+        ScoringMode::Vegas => bankroll + VEGAS_BUY_IN,
+    };
+    *score = Score {
+        mode: settings.mode,
+        base,
+        bonus: 0,
+        points: base,
+    };
+    score_writer.send(ScoreChanged {
+        points: score.points,
+        mode: score.mode,
+    });
+}
+
+fn award_time_bonus(mut events: EventReader<GameWonEvent>, mut score: ResMut<Score>) {
+    let Some(won) = events.read().last() else {
+        return;
+    };
+
+    let seconds = won.elapsed.as_secs();
+    if score.mode == ScoringMode::Standard && seconds >= TIME_BONUS_MIN_SECS {
+        score.bonus = (TIME_BONUS_NUMERATOR / seconds) as i32;
+    }
+}
+
+pub fn update_score(
+    history: Res<History>,
+    deck: Res<Deck>,
+    clock: Res<GameClock>,
+    mut score: ResMut<Score>,
+    mut score_writer: EventWriter<ScoreChanged>,
+) {
+    let moves = move_points(score.mode, deck.get_draw_mode(), history.records());
+    let points = match score.mode {
+        ScoringMode::Standard => {
+            let intervals = clock.0.elapsed().as_secs() / TIME_PENALTY_INTERVAL;
+            let penalty = intervals as i32 * TIME_PENALTY;
+            (score.base + moves + penalty).max(0) + score.bonus
+        }
+        ScoringMode::Vegas => score.base + moves,
+    };
+
+    if points != score.points {
+        score.points = points;
+        score_writer.send(ScoreChanged {
+            points,
+            mode: score.mode,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(mv: Move) -> Record {
+        Record {
+            mv,
+            revealed: false,
+            drawn: 0,
+        }
+    }
+
+    fn transfer(from: Pile, to: Pile) -> Record {
+        record(Move::Transfer { from, to, count: 1 })
+    }
+
+    fn standard(records: &[Record]) -> i32 {
+        move_points(ScoringMode::Standard, DrawMode::One, records)
+    }
+
+    #[test]
+    fn standard_points_per_move() {
+        assert_eq!(standard(&[transfer(Pile::Waste, Pile::Tableau(0))]), 5);
+        assert_eq!(standard(&[transfer(Pile::Waste, Pile::Foundation(0))]), 10);
+        assert_eq!(
+            standard(&[transfer(Pile::Tableau(0), Pile::Foundation(0))]),
+            10
+        );
+        assert_eq!(
+            standard(&[transfer(Pile::Foundation(0), Pile::Tableau(0))]),
+            -15
+        );
+        assert_eq!(standard(&[transfer(Pile::Tableau(0), Pile::Tableau(1))]), 0);
+        assert_eq!(standard(&[record(Move::Draw)]), 0);
+    }
+
+    #[test]
+    fn revealing_a_card_scores_on_top_of_the_move() {
+        let reveal = Record {
+            revealed: true,
+            ..transfer(Pile::Tableau(0), Pile::Tableau(1))
+        };
+        assert_eq!(standard(&[reveal]), 5);
+
+        let reveal_home = Record {
+            revealed: true,
+            ..transfer(Pile::Tableau(0), Pile::Foundation(0))
+        };
+        assert_eq!(standard(&[reveal_home]), 15);
+    }
+
+    #[test]
+    fn draw_one_recycles_always_cost_points() {
+        let recycles = vec![record(Move::Recycle); 2];
+        assert_eq!(standard(&recycles[..1]), -100);
+        assert_eq!(standard(&recycles), -200);
+    }
+
+    #[test]
+    fn draw_three_recycles_are_free_for_three_passes() {
+        let recycles = vec![record(Move::Recycle); FREE_RECYCLES_DRAW_THREE + 2];
+        let points =
+            |count: usize| move_points(ScoringMode::Standard, DrawMode::Three, &recycles[..count]);
+        assert_eq!(points(FREE_RECYCLES_DRAW_THREE), 0);
+        assert_eq!(points(FREE_RECYCLES_DRAW_THREE + 1), -20);
+        assert_eq!(points(FREE_RECYCLES_DRAW_THREE + 2), -40);
+    }
+
+    #[test]
+    fn vegas_only_scores_foundation_moves() {
+        let vegas = |records: &[Record]| move_points(ScoringMode::Vegas, DrawMode::One, records);
+        assert_eq!(vegas(&[transfer(Pile::Waste, Pile::Foundation(0))]), 5);
+        assert_eq!(
+            vegas(&[transfer(Pile::Foundation(0), Pile::Tableau(0))]),
+            -5
+        );
+        assert_eq!(vegas(&[transfer(Pile::Waste, Pile::Tableau(0))]), 0);
+        assert_eq!(vegas(&[record(Move::Recycle)]), 0);
+    }
+
+    #[test]
+    fn undoing_a_move_takes_its_points_back() {
+        let mut history = History::default();
+        history.push(transfer(Pile::Waste, Pile::Tableau(0)));
+        history.push(Record {
+            revealed: true,
+            ..transfer(Pile::Tableau(1), Pile::Foundation(0))
+        });
+        assert_eq!(standard(history.records()), 20);
+
+        history.undo();
+        assert_eq!(standard(history.records()), 5);
+        history.redo();
+        assert_eq!(standard(history.records()), 20);
+    }
+}
@@ -0,0 +1,347 @@
+//! Headless Klondike solver.
+//!
+//! A depth-first search over `rules::GameState` that tries the most promising
+//! moves first, skips positions it has already seen and plays provably safe
+//! foundation moves without branching on them. Like the rules engine it never
+//! touches the ECS, so it can run in tests and from `solitaire --solve`
+//! without a window.
+
+use crate::card::{Card, Suit};
+use crate::deck::Deck;
+use crate::rules::{DrawMode, GameState, Move, Pile};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
+
+/// Marks the end of a column or pile in a position key; no card code uses it.
+const KEY_SEPARATOR: u8 = u8::MAX;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Budget {
+    /// Distinct positions to expand before giving up.
+    pub max_nodes: usize,
+    pub max_time: Option<Duration>,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            max_nodes: 200_000,
+            max_time: Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Verdict {
+    /// Every move from the starting position to a won game, in order.
+    Winnable(Vec<Move>),
+    Unwinnable,
+    /// The budget ran out before the search could decide.
+    Unknown,
+}
+
+/// Solves a deck's deal in the order `setup_cards` consumes it.
+pub fn solve_deck(deck: &Deck, budget: Budget) -> Verdict {
+    solve(&GameState::from_deck(deck), budget)
+}
+
+/// `solitaire --solve <seed> [--draw-three]` says whether that game can be won
+/// instead of opening the window.
+pub fn solve_request_from_args() -> Option<Deck> {
+    let draw_mode = if std::env::args().any(|arg| arg == "--draw-three") {
+        DrawMode::Three
+    } else {
+        DrawMode::One
+    };
+    let mut args = std::env::args().skip_while(|arg| arg != "--solve");
+    args.next()?;
+    let seed = args.next()?.parse().ok()?;
+    Some(Deck::with_seed_and_mode(seed, draw_mode))
+}
+
+pub fn print_verdict(deck: &Deck) {
+    let game = format!(
+        "Game #{} (draw {})",
+        deck.get_seed(),
+        deck.get_draw_mode().count()
+    );
+    match solve_deck(deck, Budget::default()) {
+        Verdict::Winnable(moves) => {
+            println!("{game} is winnable in {} moves:", moves.len());
+            for mv in moves {
+                println!("  {mv:?}");
+            }
+        }
+        Verdict::Unwinnable => println!("{game} can't be won"),
+        Verdict::Unknown => println!("{game} couldn't be decided within the search budget"),
+    }
+}
+
+struct Frame {
+    state: GameState,
+    moves: Vec<Move>,
+    next: usize,
+    path_len: usize,
+}
+
+pub fn solve(start: &GameState, budget: Budget) -> Verdict {
+    let started = Instant::now();
+    let mut seen = HashSet::new();
+    let mut path = vec![];
+
+    let mut root = start.clone();
+    play_safe_moves(&mut root, &mut path);
+    if root.is_won() {
+        return Verdict::Winnable(path);
+    }
+    seen.insert(position_key(&root));
+
+    let (moves, mut skipped) = ordered_moves(&root);
+    let mut stack = vec![Frame {
+        moves,
+        state: root,
+        next: 0,
+        path_len: path.len(),
+    }];
+
+    while let Some(frame) = stack.last_mut() {
+        if seen.len() > budget.max_nodes
+            || budget
+                .max_time
+                .is_some_and(|limit| started.elapsed() > limit)
+        {
+            return Verdict::Unknown;
+        }
+
+        let Some(&mv) = frame.moves.get(frame.next) else {
+            stack.pop();
+            continue;
+        };
+        frame.next += 1;
+
+        let mut child = frame.state.clone();
+        if child.apply(mv).is_err() {
+            continue;
+        }
+        path.truncate(frame.path_len);
+        path.push(mv);
+        play_safe_moves(&mut child, &mut path);
+
+        if child.is_won() {
+            return Verdict::Winnable(path);
+        }
+        if !seen.insert(position_key(&child)) {
+            continue;
+        }
+
+        let (moves, skipped_here) = ordered_moves(&child);
+        skipped |= skipped_here;
+        stack.push(Frame {
+            moves,
+            state: child,
+            next: 0,
+            path_len: path.len(),
+        });
+    }
+
+    if skipped {
+        Verdict::Unknown
+    } else {
+        Verdict::Unwinnable
+    }
+}
+
+fn play_safe_moves(state: &mut GameState, path: &mut Vec<Move>) {
+    while let Some(mv) = state.next_safe_foundation_move() {
+        if state.apply(mv).is_err() {
+            return;
+        }
+        path.push(mv);
+    }
+}
+
+fn move_priority(state: &GameState, mv: &Move) -> u8 {
+    match *mv {
+        Move::Transfer {
+            to: Pile::Foundation(_),
+            ..
+        } => 0,
+        Move::Transfer {
+            from: Pile::Tableau(i),
+            count,
+            ..
+        } => {
+            let column = &state.tableau[i];
+            if count == column.face_up.len() && !column.face_down.is_empty() {
+                1
+            } else {
+                3
+            }
+        }
+        Move::Transfer {
+            from: Pile::Waste, ..
+        } => 2,
+        Move::Draw | Move::Recycle => 4,
+        Move::Transfer { .. } => 5,
+    }
+}
+
+/// Legal moves worth exploring, best first, and whether any were left out by
+/// `is_unpromising` rather than being provably useless.
+fn ordered_moves(state: &GameState) -> (Vec<Move>, bool) {
+    let mut skipped = false;
+    let mut moves: Vec<Move> = state
+        .legal_moves()
+        .into_iter()
+        .filter(|mv| !is_redundant(state, mv))
+        .filter(|mv| {
+            let unpromising = is_unpromising(state, mv);
+            skipped |= unpromising;
+            !unpromising
+        })
+        .collect();
+    moves.sort_by_key(|mv| move_priority(state, mv));
+    (moves, skipped)
+}
+
+/// Moving a whole column onto an empty column only swaps two columns round.
+fn is_redundant(state: &GameState, mv: &Move) -> bool {
+    match *mv {
+        Move::Transfer {
+            from: Pile::Tableau(from),
+            to: Pile::Tableau(to),
+            count,
+        } => {
+            let source = &state.tableau[from];
+            source.face_down.is_empty()
+                && count == source.face_up.len()
+                && state.tableau[to].is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// Splitting a run is rarely useful unless the card it uncovers can go home.
+/// Skipping these keeps the search small but means it can no longer prove a
+/// deal unwinnable.
+fn is_unpromising(state: &GameState, mv: &Move) -> bool {
+    match *mv {
+        Move::Transfer {
+            from: Pile::Tableau(from),
+            to: Pile::Tableau(_),
+            count,
+        } => {
+            let source = &state.tableau[from];
+            count < source.face_up.len() && {
+                let exposed = &source.face_up[source.face_up.len() - count - 1];
+                !state
+                    .foundations
+                    .iter()
+                    .any(|pile| exposed.can_move_home(pile.last()))
+            }
+        }
+        _ => false,
+    }
+}
+
+fn card_code(card: &Card) -> u8 {
+    card.suit.clone() as u8 * 13 + card.rank.clone() as u8
+}
+
+/// The position with tableau column and foundation order ignored, since
+/// swapping those round doesn't change how the game plays out. The key is the
+/// position itself rather than a hash of it, so no two positions can collide
+/// and prune a branch that was never searched.
+fn position_key(state: &GameState) -> Vec<u8> {
+    let mut columns: Vec<Vec<u8>> = state
+        .tableau
+        .iter()
+        .map(|column| {
+            let face_down = column.face_down.iter().map(|c| card_code(c) | 0x80);
+            let face_up = column.face_up.iter().map(card_code);
+            face_down.chain(face_up).collect()
+        })
+        .collect();
+    columns.sort();
+
+    let mut key = vec![];
+    for column in columns {
+        key.extend(column);
+        key.push(KEY_SEPARATOR);
+    }
+    for suit in Suit::iter() {
+        let height = state
+            .foundations
+            .iter()
+            .filter_map(|pile| pile.last())
+            .find(|top| top.suit == suit)
+            .map_or(0, |top| top.rank.value());
+        key.push(height);
+    }
+    key.extend(state.stock.iter().map(card_code));
+    key.push(KEY_SEPARATOR);
+    key.extend(state.waste.iter().map(card_code));
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Rank;
+    use crate::rules::Column;
+
+    /// Node-bounded only, so the result can't depend on how fast the machine is.
+    const TEST_BUDGET: Budget = Budget {
+        max_nodes: 200_000,
+        max_time: None,
+    };
+
+    #[test]
+    fn solves_a_winnable_deal_with_moves_that_replay() {
+        let deck = Deck::with_seed(4);
+        let Verdict::Winnable(moves) = solve_deck(&deck, TEST_BUDGET) else {
+            panic!("game #4 should be winnable");
+        };
+
+        let mut game = GameState::from_deck(&deck);
+        for mv in moves {
+            assert_eq!(game.apply(mv).map(|_| ()), Ok(()), "{mv:?} was illegal");
+        }
+        assert!(game.is_won());
+    }
+
+    #[test]
+    fn gives_up_when_the_budget_runs_out() {
+        let budget = Budget {
+            max_nodes: 0,
+            max_time: None,
+        };
+        assert_eq!(solve_deck(&Deck::with_seed(4), budget), Verdict::Unknown);
+    }
+
+    #[test]
+    fn proves_a_stuck_position_unwinnable() {
+        let mut tableau = vec![Column::default(); 7];
+        tableau[0] = Column {
+            face_down: vec![Card {
+                rank: Rank::Ace,
+                suit: Suit::Spades,
+                flipped: true,
+            }],
+            face_up: vec![Card {
+                rank: Rank::Two,
+                suit: Suit::Hearts,
+                flipped: false,
+            }],
+        };
+        let game = GameState {
+            stock: vec![],
+            waste: vec![],
+            tableau,
+            foundations: vec![vec![]; 4],
+            draw_mode: DrawMode::One,
+        };
+        assert_eq!(solve(&game, TEST_BUDGET), Verdict::Unwinnable);
+    }
+}
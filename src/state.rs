@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppState {
+    /// Waiting for the card textures before anything is shown.
+    #[default]
+    Loading,
+    MainMenu,
+    Playing,
+    Paused,
+    Won,
+    /// Watching a recorded game play back rather than playing.
+    Replay,
+}
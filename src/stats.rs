@@ -0,0 +1,328 @@
+use crate::deck::Deck;
+use crate::events::{GameWonEvent, NewGameEvent};
+use crate::history::History;
+use crate::hud::{HUD_FONT_SIZE, HUD_TEXT_COLOUR};
+use crate::rules::DrawMode;
+use crate::score::{Score, update_score};
+use crate::state::AppState;
+use crate::storage;
+use crate::utils::button::{CancelButton, spawn_button};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const STATS_FILE: &str = "stats.ron";
+/// Bump when `Stats` changes shape; older files are then started afresh.
+const STATS_VERSION: u32 = 1;
+const STATS_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Stats::load())
+            .init_resource::<GameTally>()
+            .add_systems(
+                Update,
+                (
+                    count_game_played.run_if(in_state(AppState::Playing)),
+                    record_win.after(update_score),
+                    (
+                        // Only over menus; while playing, the board under it
+                        // would still take clicks.
+                        toggle_stats_screen
+                            .run_if(in_state(AppState::MainMenu).or(in_state(AppState::Won))),
+                        handle_stats_buttons,
+                    )
+                        .chain(),
+                ),
+            )
+            .add_systems(OnEnter(AppState::Playing), close_stats_screen)
+            // Last, so a quit requested anywhere in the frame is still seen.
+            .add_systems(Last, record_abandoned_game);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct ModeStats {
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub fastest_win_secs: Option<u64>,
+    pub fewest_moves: Option<usize>,
+    pub best_score: Option<i32>,
+}
+
+impl ModeStats {
+    pub fn win_percentage(&self) -> f32 {
+        if self.played == 0 {
+            0.0
+        } else {
+            self.won as f32 * 100.0 / self.played as f32
+        }
+    }
+}
+
+/// Lifetime statistics, kept separately for each draw mode.
+#[derive(Resource, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Stats {
+    version: u32,
+    draw_one: ModeStats,
+    draw_three: ModeStats,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats {
+            version: STATS_VERSION,
+            draw_one: ModeStats::default(),
+            draw_three: ModeStats::default(),
+        }
+    }
+}
+
+impl Stats {
+    pub fn load() -> Self {
+        storage::load::<Stats>(STATS_FILE)
+            .filter(|stats| stats.version == STATS_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(STATS_FILE, self);
+    }
+
+    pub fn get(&self, draw_mode: DrawMode) -> &ModeStats {
+        match draw_mode {
+            DrawMode::One => &self.draw_one,
+            DrawMode::Three => &self.draw_three,
+        }
+    }
+
+    fn get_mut(&mut self, draw_mode: DrawMode) -> &mut ModeStats {
+        match draw_mode {
+            DrawMode::One => &mut self.draw_one,
+            DrawMode::Three => &mut self.draw_three,
+        }
+    }
+}
+
+/// Whether the current deal has been counted as played and not yet finished.
+#[derive(Resource, Default, Debug)]
+pub struct GameTally {
+    pub in_progress: Option<DrawMode>,
+}
+
+#[derive(Component)]
+struct StatsScreen;
+
+#[derive(Component, Clone, Copy)]
+enum StatsButton {
+    Reset,
+    Close,
+}
+
+/// A deal only counts as played once the first move is made.
+fn count_game_played(
+    history: Res<History>,
+    deck: Res<Deck>,
+    mut tally: ResMut<GameTally>,
+    mut stats: ResMut<Stats>,
+) {
+    if !history.is_changed() || history.len() == 0 || tally.in_progress.is_some() {
+        return;
+    }
+
+    let draw_mode = deck.get_draw_mode();
+    tally.in_progress = Some(draw_mode);
+    stats.get_mut(draw_mode).played += 1;
+    stats.save();
+}
+
+fn record_win(
+    mut events: EventReader<GameWonEvent>,
+    score: Res<Score>,
+    mut tally: ResMut<GameTally>,
+    mut stats: ResMut<Stats>,
+) {
+    let Some(won) = events.read().last() else {
+        return;
+    };
+
+    tally.in_progress = None;
+    let mode_stats = stats.get_mut(won.draw_mode);
+    let seconds = won.elapsed.as_secs();
+    mode_stats.won += 1;
+    mode_stats.current_streak += 1;
+    mode_stats.best_streak = mode_stats.best_streak.max(mode_stats.current_streak);
+    mode_stats.fastest_win_secs = Some(
+        mode_stats
+            .fastest_win_secs
+            .map_or(seconds, |s| s.min(seconds)),
+    );
+    mode_stats.fewest_moves = Some(
+        mode_stats
+            .fewest_moves
+            .map_or(won.moves, |m| m.min(won.moves)),
+    );
+    mode_stats.best_score = Some(
+        mode_stats
+            .best_score
+            .map_or(score.get_points(), |s| s.max(score.get_points())),
+    );
+    stats.save();
+}
+
+/// Dealing again or quitting part way through a game ends the win streak.
+fn record_abandoned_game(
+    mut new_game_events: EventReader<NewGameEvent>,
+    mut exit_events: EventReader<AppExit>,
+    mut tally: ResMut<GameTally>,
+    mut stats: ResMut<Stats>,
+) {
+    let ended = new_game_events.read().last().is_some() | exit_events.read().last().is_some();
+    if !ended {
+        return;
+    }
+    let Some(draw_mode) = tally.in_progress.take() else {
+        return;
+    };
+
+    stats.get_mut(draw_mode).current_streak = 0;
+    stats.save();
+}
+
+fn toggle_stats_screen(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    stats: Res<Stats>,
+    screens: Query<Entity, With<StatsScreen>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyS) {
+        return;
+    }
+
+    if screens.is_empty() {
+        spawn_stats_screen(&mut commands, &stats);
+    } else {
+        for entity in screens.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn close_stats_screen(mut commands: Commands, screens: Query<Entity, With<StatsScreen>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn handle_stats_buttons(
+    mut commands: Commands,
+    mut stats: ResMut<Stats>,
+    buttons: Query<(&Interaction, &StatsButton), Changed<Interaction>>,
+    screens: Query<Entity, With<StatsScreen>>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        for entity in screens.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        if let StatsButton::Reset = button {
+            *stats = Stats::default();
+            stats.save();
+            spawn_stats_screen(&mut commands, &stats);
+        }
+    }
+}
+
+fn stats_rows(stats: &ModeStats) -> Vec<String> {
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    vec![
+        stats.played.to_string(),
+        stats.won.to_string(),
+        format!("{:.0}%", stats.win_percentage()),
+        stats.current_streak.to_string(),
+        stats.best_streak.to_string(),
+        or_dash(
+            stats
+                .fastest_win_secs
+                .map(|s| format!("{}:{:02}", s / 60, s % 60)),
+        ),
+        or_dash(stats.fewest_moves.map(|m| m.to_string())),
+        or_dash(stats.best_score.map(|s| s.to_string())),
+    ]
+}
+
+pub fn spawn_stats_screen(commands: &mut Commands, stats: &Stats) {
+    let labels = [
+        "",
+        "Played",
+        "Won",
+        "Win rate",
+        "Current streak",
+        "Best streak",
+        "Fastest win",
+        "Fewest moves",
+        "Best score",
+    ];
+    let columns = [
+        labels.iter().map(|l| l.to_string()).collect::<Vec<_>>(),
+        std::iter::once("Draw 1".to_string())
+            .chain(stats_rows(stats.get(DrawMode::One)))
+            .collect(),
+        std::iter::once("Draw 3".to_string())
+            .chain(stats_rows(stats.get(DrawMode::Three)))
+            .collect(),
+    ];
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(STATS_BACKGROUND),
+            GlobalZIndex(10),
+            StatsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Statistics"),
+                TextFont {
+                    font_size: HUD_FONT_SIZE * 2.0,
+                    ..default()
+                },
+                TextColor(HUD_TEXT_COLOUR),
+            ));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(32.0),
+                    ..default()
+                })
+                .with_children(|table| {
+                    for column in columns {
+                        table.spawn((
+                            Text::new(column.join("\n")),
+                            TextFont {
+                                font_size: HUD_FONT_SIZE,
+                                ..default()
+                            },
+                            TextColor(HUD_TEXT_COLOUR),
+                        ));
+                    }
+                });
+            spawn_button(parent, "Reset statistics", StatsButton::Reset);
+            spawn_button(parent, "Close (S)", (StatsButton::Close, CancelButton));
+        });
+}
@@ -0,0 +1,60 @@
+//! Small RON files kept in the platform's user data directory.
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const APP_DIR: &str = "rust-solitaire";
+
+pub fn data_path(file: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR).join(file))
+}
+
+/// Reads `file` back, or `None` if it is missing or unreadable.
+pub fn load<T: DeserializeOwned>(file: &str) -> Option<T> {
+    load_from(&data_path(file)?)
+}
+
+/// Like `load`, for a file anywhere on disk.
+pub fn load_from<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = fs::read_to_string(path).ok()?;
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Ignoring unreadable {}: {}", path.display(), error);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(file: &str, value: &T) {
+    let Some(path) = data_path(file) else {
+        warn!("No user data directory to save {} in", file);
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            ron::ser::to_string_pretty(value, PrettyConfig::default()).map_err(io::Error::other)
+        })
+        .and_then(|contents| fs::write(&path, contents));
+    if let Err(error) = result {
+        warn!("Failed to save {}: {}", path.display(), error);
+    }
+}
+
+pub fn remove(file: &str) {
+    let Some(path) = data_path(file) else {
+        return;
+    };
+    if let Err(error) = fs::remove_file(&path)
+        && error.kind() != io::ErrorKind::NotFound
+    {
+        warn!("Failed to remove {}: {}", path.display(), error);
+    }
+}
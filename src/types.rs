@@ -11,7 +11,7 @@ pub type DeckCardFilter = (
     Without<Slot>,
     Without<DrawPosition>,
 );
-pub type DrawCardFilter = (
+pub type _DrawCardFilter = (
     With<Hoverable>,
     With<Card>,
     With<DrawPosition>,
@@ -19,7 +19,7 @@ pub type DrawCardFilter = (
     Without<DeckPosition>,
 );
 
-pub type DrawSlotFilter = (With<Slot>, With<DrawPosition>);
+pub type _DrawSlotFilter = (With<Slot>, With<DrawPosition>);
 pub type DeckSlotFilter = (With<Slot>, With<DeckPosition>);
 pub type _ColSlotFilter = (With<Slot>, With<Col>);
 pub type _HomeSlotFilter = (With<Slot>, With<Home>);
@@ -0,0 +1,47 @@
+use crate::hud::{HUD_FONT_SIZE, HUD_TEXT_COLOUR};
+use bevy::prelude::*;
+
+pub const BUTTON_COLOUR: Color = Color::srgb(0.15, 0.15, 0.15);
+const BUTTON_HOVER_COLOUR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+/// Marks the button that backs out of its menu, pressed by Escape or a
+/// gamepad's East button.
+#[derive(Component)]
+pub struct CancelButton;
+
+pub fn spawn_button<T: Bundle>(parent: &mut ChildBuilder, label: &str, marker: T) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(220.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOUR),
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: HUD_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(HUD_TEXT_COLOUR),
+            ));
+        });
+}
+
+/// Shared hover feedback for every menu button in the game.
+pub fn highlight_buttons(
+    mut buttons: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut colour) in buttons.iter_mut() {
+        colour.0 = match interaction {
+            Interaction::Hovered | Interaction::Pressed => BUTTON_HOVER_COLOUR,
+            Interaction::None => BUTTON_COLOUR,
+        };
+    }
+}
@@ -0,0 +1,35 @@
+use crate::events::DoubleClickEvent;
+use crate::types::{CardFilter, CardSimpleHoverItem};
+use bevy::prelude::*;
+
+const DOUBLE_CLICK_WINDOW: f64 = 0.35;
+
+/// The card under the last left click, and when it happened.
+#[derive(Resource, Default, Debug)]
+pub struct LastClick(Option<(Entity, f64)>);
+
+pub fn detect_double_click(
+    time: Res<Time>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut last_click: ResMut<LastClick>,
+    query: Query<CardSimpleHoverItem, CardFilter>,
+    mut double_click_writer: EventWriter<DoubleClickEvent>,
+) {
+    if !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let now = time.elapsed_secs_f64();
+    let Some((entity, _)) = query.iter().find(|(_, hover_state)| hover_state.hovering) else {
+        last_click.0 = None;
+        return;
+    };
+
+    match last_click.0 {
+        Some((last, at)) if last == entity && now - at <= DOUBLE_CLICK_WINDOW => {
+            double_click_writer.send(DoubleClickEvent(entity));
+            last_click.0 = None;
+        }
+        _ => last_click.0 = Some((entity, now)),
+    }
+}
@@ -19,11 +19,11 @@ pub fn update_cursor(
     let window = windows.single();
 
     let mut found_cursor_position = false;
-    if let Some(cursor_position) = window.cursor_position() {
-        if let Ok(world_position) = camera.viewport_to_world(camera_transform, cursor_position) {
-            cursor_world.position = Some(world_position.origin.truncate());
-            found_cursor_position = true;
-        }
+    if let Some(cursor_position) = window.cursor_position()
+        && let Ok(world_position) = camera.viewport_to_world(camera_transform, cursor_position)
+    {
+        cursor_world.position = Some(world_position.origin.truncate());
+        found_cursor_position = true;
     }
 
     if !found_cursor_position {
@@ -1,5 +1,8 @@
-use crate::board::BoardState;
+use crate::board::{BoardState, Col, Home, Slot};
 use crate::card::Card;
+use crate::deck::Deck;
+use crate::events::MoveEvent;
+use crate::rules::{Move, Pile};
 use crate::utils::cursor::Cursor;
 use crate::utils::hovering::HoverState;
 use bevy::prelude::*;
@@ -39,7 +42,7 @@ pub fn start_drag_system(
                         for pile_card in pile {
                             if add_card {
                                 target_cards.push(pile_card);
-                            } else if card == pile_card {
+                            } else if card.matches(pile_card) {
                                 target_cards.push(pile_card);
                                 add_card = true
                             }
@@ -86,15 +89,72 @@ pub fn drag_system(
     }
 }
 
+pub fn find_source(board_state: &BoardState, deck: &Deck, head: &Card) -> Option<Pile> {
+    let contains = |pile: &Vec<Card>| pile.iter().any(|c| c.matches(head));
+    if let Some(i) = board_state.play_piles.iter().position(contains) {
+        return Some(Pile::Tableau(i));
+    }
+    if let Some(i) = board_state.home_piles.iter().position(contains) {
+        return Some(Pile::Foundation(i));
+    }
+    deck.get_drawn_cards()
+        .last()
+        .filter(|c| c.matches(head))
+        .map(|_| Pile::Waste)
+}
+
 pub fn stop_drag_system(
     buttons: Res<ButtonInput<MouseButton>>,
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Transform, &Dragging)>,
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    slots: Query<
+        (&HoverState, Option<&Col>, Option<&Home>),
+        (With<Slot>, Or<(With<Col>, With<Home>)>, Without<Dragging>),
+    >,
+    mut query: Query<(Entity, &mut Transform, &Dragging, &Card)>,
+    mut move_writer: EventWriter<MoveEvent>,
 ) {
-    if buttons.just_released(MouseButton::Left) {
-        for (entity, mut transform, dragging) in query.iter_mut() {
+    if !buttons.just_released(MouseButton::Left) {
+        return;
+    }
+
+    let mut dragged: Vec<_> = query.iter_mut().collect();
+    dragged.sort_by(|a, b| {
+        a.2.original_position
+            .z
+            .partial_cmp(&b.2.original_position.z)
+            .unwrap()
+    });
+
+    let target = slots
+        .iter()
+        .find(|(hover_state, _, _)| hover_state.hovering)
+        .and_then(|(_, col, home)| match (col, home) {
+            (Some(col), _) => Some(Pile::Tableau(col.0 as usize)),
+            (_, Some(home)) => Some(Pile::Foundation(home.0 as usize)),
+            _ => None,
+        });
+
+    let mut dropped = false;
+    if let (Some(to), Some((_, _, _, head))) = (target, dragged.first())
+        && let Some(from) = find_source(&board_state, &deck, head)
+    {
+        let mv = Move::Transfer {
+            from,
+            to,
+            count: dragged.len(),
+        };
+        if board_state.to_game_state(&deck).is_legal(mv) {
+            move_writer.send(MoveEvent(mv));
+            dropped = true;
+        }
+    }
+
+    for (entity, mut transform, dragging, _) in dragged {
+        if !dropped {
             transform.translation = dragging.original_position;
-            commands.entity(entity).remove::<Dragging>();
         }
+        commands.entity(entity).remove::<Dragging>();
     }
 }
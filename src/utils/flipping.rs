@@ -1,4 +1,4 @@
-use crate::card::Card;
+use crate::card::{Card, CardBack};
 use bevy::prelude::*;
 use std::f32::consts::{FRAC_PI_2, PI};
 
@@ -7,6 +7,19 @@ pub struct Flipping {
     pub speed: f32,
     pub flipped: bool,
     pub progress: f32,
+    /// Turn the card onto its back instead of face-up.
+    pub face_down: bool,
+}
+
+impl Flipping {
+    pub fn new(face_down: bool) -> Self {
+        Flipping {
+            speed: PI * 3.0,
+            flipped: false,
+            progress: 0.0,
+            face_down,
+        }
+    }
 }
 
 pub fn handle_flip(
@@ -20,6 +33,7 @@ pub fn handle_flip(
         &mut Card,
     )>,
     server: Res<AssetServer>,
+    back: Res<CardBack>,
 ) {
     for (entity, mut transform, mut flipping, mut sprite, mut card) in query.iter_mut() {
         let delta_rotation = flipping.speed * time.delta_secs();
@@ -27,8 +41,12 @@ pub fn handle_flip(
         transform.rotation = Quat::from_rotation_y(flipping.progress);
 
         if !flipping.flipped && flipping.progress >= FRAC_PI_2 {
-            sprite.image = card.asset(&server);
-            card.flipped = false;
+            sprite.image = if flipping.face_down {
+                back.asset(&server)
+            } else {
+                card.asset(&server)
+            };
+            card.flipped = flipping.face_down;
             flipping.flipped = true;
 
             transform.scale.x *= -1.0;
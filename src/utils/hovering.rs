@@ -1,5 +1,6 @@
-use crate::board::{Col, DeckPosition, Home, Slot};
+use crate::board::{BoardState, Col, DeckPosition, Home, Slot};
 use crate::events::{HoverEnterEvent, HoverExitEvent};
+use crate::layout::Layout;
 use crate::types::{CardFilter, CardHoverItem, CardSimpleHoverItem, HoverItem};
 use crate::utils::cursor::Cursor;
 use crate::utils::debug::draw_debug_box;
@@ -13,6 +14,9 @@ pub struct Hoverable;
 #[derive(Component, Default)]
 pub struct HoverState {
     pub hovering: bool,
+    /// Under the keyboard or gamepad focus cursor, which keeps the card lit
+    /// even once the mouse leaves it.
+    pub focused: bool,
 }
 
 pub fn hover_card_system(
@@ -96,8 +100,11 @@ pub fn hover_deck_system(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn hover_play_slot_system(
     cursor: Res<Cursor>,
+    board_state: Res<BoardState>,
+    layout: Res<Layout>,
     mut slot_q: Query<
         (HoverItem, Option<&Col>, Option<&Home>),
         (With<Hoverable>, With<Slot>, Or<(With<Col>, With<Home>)>),
@@ -114,10 +121,14 @@ pub fn hover_play_slot_system(
     for ((entity, transform, sprite, mut hover_state), col, home) in slot_q.iter_mut() {
         let mut position = transform.translation.truncate();
         let mut size = sprite.custom_size.unwrap_or(Vec2::ONE);
-        if col.is_some() {
-            position.y -= size.y * 2f32;
+        // A column can be dropped on anywhere from its slot down to its last card.
+        if let Some(col) = col {
+            let pile = &board_state.play_piles[col.0 as usize];
+            let face_down = pile.iter().filter(|card| card.flipped).count();
+            let depth = layout.column_depth(face_down, pile.len() - face_down);
+            position.y -= depth / 2.0;
             size.x *= 1.3;
-            size.y *= 5f32;
+            size.y = size.y * 1.3 + depth;
         }
         if home.is_some() {
             size *= 1.3;
@@ -1,3 +1,5 @@
+pub mod button;
+pub mod clicking;
 pub mod cursor;
 pub mod debug;
 pub mod dragging;
@@ -5,6 +7,9 @@ pub mod flipping;
 pub mod hovering;
 pub mod moveto;
 
+use crate::state::AppState;
+use crate::utils::button::highlight_buttons;
+use crate::utils::clicking::{LastClick, detect_double_click};
 use crate::utils::cursor::{Cursor, update_cursor, update_cursor_icon};
 use crate::utils::debug::{DebugMode, toggle_debug_mode};
 use crate::utils::dragging::{drag_system, start_drag_system, stop_drag_system};
@@ -21,21 +26,27 @@ impl Plugin for UtilsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Cursor>()
             .init_resource::<DebugMode>()
+            .init_resource::<LastClick>()
             .add_systems(PreUpdate, update_cursor)
             .add_systems(
                 Update,
                 (
                     update_cursor_icon,
-                    hover_card_system,
-                    hover_play_slot_system,
-                    hover_deck_system,
+                    hover_card_system.run_if(in_state(AppState::Playing)),
+                    (hover_play_slot_system, hover_deck_system).run_if(in_state(AppState::Playing)),
                     reset_hover_flags,
                     handle_flip,
                     handle_move_to,
-                    start_drag_system,
-                    drag_system,
-                    stop_drag_system,
+                    (
+                        start_drag_system,
+                        detect_double_click,
+                        drag_system,
+                        stop_drag_system,
+                    )
+                        .chain()
+                        .run_if(in_state(AppState::Playing)),
                     toggle_debug_mode,
+                    highlight_buttons,
                 ),
             );
     }
@@ -0,0 +1,272 @@
+use crate::board::{BoardState, Slot};
+use crate::card::Card;
+use crate::clock::GameClock;
+use crate::deck::Deck;
+use crate::events::{GameWonEvent, NewGameEvent};
+use crate::history::History;
+use crate::hud::{HUD_FONT_SIZE, HUD_TEXT_COLOUR};
+use crate::layout::Layout;
+use crate::moves::apply_moves;
+use crate::state::AppState;
+use crate::utils::button::spawn_button;
+use crate::utils::flipping::Flipping;
+use crate::utils::moveto::MoveTo;
+use bevy::prelude::*;
+use rand::Rng;
+
+const CASCADE_INTERVAL: f32 = 0.12;
+const CASCADE_GRAVITY: f32 = -1800.0;
+const CASCADE_RESTITUTION: f32 = 0.75;
+
+pub struct WinPlugin;
+
+impl Plugin for WinPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            // Also checked on returning to play, as a change made just before a
+            // pause is used up by `resource_changed` while paused.
+            detect_win
+                .after(apply_moves)
+                .run_if(in_state(AppState::Playing))
+                .run_if(resource_changed::<BoardState>.or(state_changed::<AppState>)),
+        )
+        .add_systems(OnEnter(AppState::Won), start_cascade)
+        .add_systems(OnExit(AppState::Won), stop_cascade)
+        .add_systems(
+            Update,
+            (run_cascade, handle_win_menu).run_if(in_state(AppState::Won)),
+        );
+    }
+}
+
+/// Drives the end-of-game card cascade, launching one foundation card at a time.
+#[derive(Resource)]
+struct Cascade {
+    timer: Timer,
+    next_pile: usize,
+    launched: usize,
+    summary: GameWonEvent,
+    menu_shown: bool,
+}
+
+#[derive(Component)]
+struct Bouncing {
+    velocity: Vec2,
+}
+
+#[derive(Component, Clone, Copy)]
+enum WinMenuButton {
+    NewGame,
+    Replay,
+    Quit,
+}
+
+pub fn detect_win(
+    board_state: Res<BoardState>,
+    deck: Res<Deck>,
+    clock: Res<GameClock>,
+    history: Res<History>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut won_writer: EventWriter<GameWonEvent>,
+) {
+    if !board_state.to_game_state(&deck).is_won() {
+        return;
+    }
+
+    won_writer.send(GameWonEvent {
+        elapsed: clock.0.elapsed(),
+        moves: history.len(),
+        seed: deck.get_seed(),
+        draw_mode: deck.get_draw_mode(),
+    });
+    next_state.set(AppState::Won);
+}
+
+fn start_cascade(mut commands: Commands, mut won_reader: EventReader<GameWonEvent>) {
+    let Some(summary) = won_reader.read().last().cloned() else {
+        return;
+    };
+
+    commands.insert_resource(Cascade {
+        timer: Timer::from_seconds(CASCADE_INTERVAL, TimerMode::Repeating),
+        next_pile: 0,
+        launched: 0,
+        summary,
+        menu_shown: false,
+    });
+}
+
+fn stop_cascade(mut commands: Commands) {
+    commands.remove_resource::<Cascade>();
+}
+
+fn run_cascade(
+    mut commands: Commands,
+    time: Res<Time>,
+    cascade: Option<ResMut<Cascade>>,
+    mut board_state: ResMut<BoardState>,
+    layout: Res<Layout>,
+    windows: Query<&Window>,
+    mut cards: Query<
+        (
+            Entity,
+            &mut Transform,
+            &Card,
+            &mut Visibility,
+            Option<&mut Bouncing>,
+        ),
+        Without<Slot>,
+    >,
+) {
+    let Some(mut cascade) = cascade else {
+        return;
+    };
+    let window = windows.single();
+    let half_width = window.width() / 2.0;
+    let half_height = window.height() / 2.0;
+    let card_size = layout.card_size();
+
+    if cascade.timer.tick(time.delta()).just_finished() {
+        let piles = board_state.home_piles.len();
+        for offset in 0..piles {
+            let index = (cascade.next_pile + offset) % piles;
+            let Some(card) = board_state.home_piles[index].pop() else {
+                continue;
+            };
+            cascade.next_pile = (index + 1) % piles;
+            cascade.launched += 1;
+
+            if let Some((entity, mut transform, ..)) =
+                cards.iter_mut().find(|(_, _, c, ..)| c.matches(&card))
+            {
+                let mut rng = rand::rng();
+                let direction = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+                transform.translation.z = 200.0 + cascade.launched as f32;
+                commands
+                    .entity(entity)
+                    .remove::<(MoveTo, Flipping)>()
+                    .insert(Bouncing {
+                        velocity: Vec2::new(
+                            direction * rng.random_range(150.0..350.0),
+                            rng.random_range(0.0..300.0),
+                        ),
+                    });
+            }
+            break;
+        }
+    }
+
+    let floor = -half_height + card_size.y / 2.0;
+    let mut still_bouncing = false;
+    for (entity, mut transform, _, mut visibility, bouncing) in cards.iter_mut() {
+        let Some(mut bouncing) = bouncing else {
+            continue;
+        };
+        let delta = time.delta_secs();
+        bouncing.velocity.y += CASCADE_GRAVITY * delta;
+        transform.translation += (bouncing.velocity * delta).extend(0.0);
+
+        if transform.translation.y < floor {
+            transform.translation.y = floor;
+            bouncing.velocity.y = -bouncing.velocity.y * CASCADE_RESTITUTION;
+        }
+
+        if transform.translation.x.abs() > half_width + card_size.x {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).remove::<Bouncing>();
+        } else {
+            still_bouncing = true;
+        }
+    }
+
+    let all_launched = board_state.home_piles.iter().all(|pile| pile.is_empty());
+    if all_launched && !still_bouncing && !cascade.menu_shown {
+        cascade.menu_shown = true;
+        spawn_win_menu(&mut commands, &cascade.summary);
+    }
+}
+
+fn spawn_win_menu(commands: &mut Commands, summary: &GameWonEvent) {
+    let seconds = summary.elapsed.as_secs();
+    let details = format!(
+        "Game #{}  -  {}:{:02}  -  {} moves",
+        summary.seed,
+        seconds / 60,
+        seconds % 60,
+        summary.moves
+    );
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            StateScoped(AppState::Won),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("You won!"),
+                TextFont {
+                    font_size: HUD_FONT_SIZE * 3.0,
+                    ..default()
+                },
+                TextColor(HUD_TEXT_COLOUR),
+            ));
+            parent.spawn((
+                Text::new(details),
+                TextFont {
+                    font_size: HUD_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(HUD_TEXT_COLOUR),
+            ));
+            for (label, button) in [
+                ("New game", WinMenuButton::NewGame),
+                ("Replay same deal", WinMenuButton::Replay),
+                ("Quit", WinMenuButton::Quit),
+            ] {
+                spawn_button(parent, label, button);
+            }
+        });
+}
+
+fn handle_win_menu(
+    cascade: Option<Res<Cascade>>,
+    buttons: Query<(&Interaction, &WinMenuButton), Changed<Interaction>>,
+    mut new_game_writer: EventWriter<NewGameEvent>,
+    mut exit_writer: EventWriter<AppExit>,
+) {
+    let Some(cascade) = cascade else {
+        return;
+    };
+
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            WinMenuButton::NewGame => {
+                new_game_writer.send(NewGameEvent {
+                    seed: None,
+                    draw_mode: cascade.summary.draw_mode,
+                });
+            }
+            WinMenuButton::Replay => {
+                new_game_writer.send(NewGameEvent {
+                    seed: Some(cascade.summary.seed),
+                    draw_mode: cascade.summary.draw_mode,
+                });
+            }
+            WinMenuButton::Quit => {
+                exit_writer.send(AppExit::Success);
+            }
+        }
+    }
+}